@@ -5,3 +5,8 @@ pub mod parser;
 pub fn unescape_literal(literal: &str) -> String {
     literal.replace(r#"\%\{"#, "%{")
 }
+
+// the inverse of unescape_literal(), so the literal can be written into a pattern
+pub fn escape_literal(literal: &str) -> String {
+    literal.replace("%{", r#"\%\{"#)
+}
@@ -1,29 +1,74 @@
 pub use self::pattern_parser::ParseError;
 
-use matcher::compiled_pattern::CompiledPattern;
-use parsers::ParserFactory;
+use std::collections::HashSet;
+
+use matcher::compiled_pattern::{CompiledPattern, TokenType};
+use parsers::{Parser, ParserFactory, ParserParams, ParserRegistry};
+use parsers::registry;
 use self::pattern_parser::ParseResult;
 
+// generated from pattern.rustpeg by rust-peg, don't edit it by hand
 #[allow(clippy)]
 mod pattern_parser;
 
-pub fn pattern_with_factory<F: ParserFactory>(input: &str) -> ParseResult<CompiledPattern> {
-    self::pattern_parser::pattern::<F>(input)
+// The grammar only splits the pattern into literals and parser invocations, the
+// parsers are created from the latter by the registry.
+pub enum PatternPiece<'input> {
+    Literal(String),
+    // the offset of the parser in the pattern, its type and its parameters
+    Parser(usize, &'input str, ParserParams<'input>),
+}
+
+pub fn pattern_with_factory<F: ParserFactory + 'static>(input: &str) -> ParseResult<CompiledPattern> {
+    let registry = ParserRegistry::from_factory::<F>();
+    pattern_with_registry(input, &registry)
+}
+
+pub fn pattern_with_registry(input: &str,
+                             registry: &ParserRegistry)
+                             -> ParseResult<CompiledPattern> {
+    let pieces = try!(self::pattern_parser::pattern(input));
+    let mut pattern = Vec::with_capacity(pieces.len());
+    for piece in pieces {
+        match piece {
+            PatternPiece::Literal(literal) => pattern.push(TokenType::Literal(literal)),
+            PatternPiece::Parser(offset, parser_type, params) => {
+                let parser = try!(create_parser(input, offset, registry, parser_type, params));
+                pattern.push(TokenType::Parser(parser));
+            }
+        }
+    }
+    Ok(pattern)
 }
 
-//
-// When you regenerate the grammar, don't forget to insert the F generic type
-// parameter with
-// the following command:
-// rm -f pattern_parser.rs; cat pattern_parser.rs.IN | sed  "s/\(fn
-// [a-zA-Z0-9_]*<'input\)/\1, F: ParserFactory/" | sed
-// "s/\(parse[a-zA-Z0-9_]*\)(/\1::<F>(/" >> pattern_parser.rs
-//
-// The first sed add the F: ParserFactory generic type parameter to every
-// function definition.
-// The second sed threads this F parameter through the call sites as well.
-//
 pub fn pattern(input: &str) -> ParseResult<CompiledPattern> {
-    use matcher::trie::parser_factory::TrieParserFactory;
-    self::pattern_parser::pattern::<TrieParserFactory>(input)
+    registry::with_global_registry(|registry| pattern_with_registry(input, registry))
+}
+
+fn create_parser(input: &str,
+                 offset: usize,
+                 registry: &ParserRegistry,
+                 parser_type: &str,
+                 params: ParserParams)
+                 -> ParseResult<Box<Parser>> {
+    registry.create(parser_type, params).map_err(|err| {
+        error!("Failed to create parser: type={} error={}", parser_type, err);
+        let (line, column) = position(input, offset);
+        let mut expected = HashSet::new();
+        expected.insert("known parser type with valid parameters");
+        ParseError {
+            line: line,
+            column: column,
+            offset: offset,
+            expected: expected,
+        }
+    })
+}
+
+// returns the line and the column of the offset, both are counted from 1
+fn position(input: &str, offset: usize) -> (usize, usize) {
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rfind('\n').map_or(offset, |newline| offset - newline - 1) + 1;
+    (line, column)
 }
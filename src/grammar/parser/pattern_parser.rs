@@ -1,12 +1,11 @@
 // Generated by rust-peg. Do not edit.
 #![allow(non_snake_case, unused)]
-use matcher::compiled_pattern::{CompiledPattern, TokenType};
-use parsers::{SetParser, IntParser, Parser, OptionalParameter, GreedyParser};
+use self::RuleResult::{Failed, Matched};
 use grammar;
-use utils;
-use parsers::ParserFactory;
+use grammar::parser::PatternPiece;
+use parsers::{OptionalParameter, ParserArgument, ParserParams};
 use std::str::FromStr;
-use self::RuleResult::{Matched, Failed};
+use utils;
 fn escape_default(s: &str) -> String {
     s.chars().flat_map(|c| c.escape_default()).collect()
 }
@@ -30,16 +29,26 @@ pub struct ParseError {
 pub type ParseResult<T> = Result<T, ParseError>;
 impl ::std::fmt::Display for ParseError {
     fn fmt(&self, fmt: &mut ::std::fmt::Formatter) -> ::std::result::Result<(), ::std::fmt::Error> {
-        try!(write!(fmt, "error at {}:{}: expected ", self.line, self.column));
+        try!(write!(
+            fmt,
+            "error at {}:{}: expected ",
+            self.line, self.column
+        ));
         if self.expected.is_empty() {
             try!(write!(fmt, "EOF"));
         } else if self.expected.len() == 1 {
-            try!(write!(fmt,
-                        "`{}`",
-                        escape_default(self.expected.iter().next().unwrap())));
+            try!(write!(
+                fmt,
+                "`{}`",
+                escape_default(self.expected.iter().next().unwrap())
+            ));
         } else {
             let mut iter = self.expected.iter();
-            try!(write!(fmt, "one of `{}`", escape_default(iter.next().unwrap())));
+            try!(write!(
+                fmt,
+                "one of `{}`",
+                escape_default(iter.next().unwrap())
+            ));
             for elem in iter {
                 try!(write!(fmt, ", `{}`", escape_default(elem)));
             }
@@ -62,11 +71,12 @@ fn slice_eq(input: &str, state: &mut ParseState, pos: usize, m: &'static str) ->
         state.mark_failure(pos, m)
     }
 }
-fn slice_eq_case_insensitive(input: &str,
-                             state: &mut ParseState,
-                             pos: usize,
-                             m: &'static str)
-                             -> RuleResult<()> {
+fn slice_eq_case_insensitive(
+    input: &str,
+    state: &mut ParseState,
+    pos: usize,
+    m: &'static str,
+) -> RuleResult<()> {
     #![inline]
     #![allow(dead_code)]
     let mut used = 0usize;
@@ -108,7 +118,7 @@ struct ParseState<'input> {
     expected: ::std::collections::HashSet<&'static str>,
     _phantom: ::std::marker::PhantomData<&'input ()>,
 }
-impl <'input> ParseState<'input> {
+impl<'input> ParseState<'input> {
     fn new() -> ParseState<'input> {
         ParseState {
             max_err_pos: 0,
@@ -127,10 +137,11 @@ impl <'input> ParseState<'input> {
         Failed
     }
 }
-fn parse_pattern<'input, F: ParserFactory>(input: &'input str,
-                                           state: &mut ParseState<'input>,
-                                           pos: usize)
-                                           -> RuleResult<CompiledPattern> {
+fn parse_pattern<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<Vec<PatternPiece<'input>>> {
     {
         let start_pos = pos;
         {
@@ -139,7 +150,7 @@ fn parse_pattern<'input, F: ParserFactory>(input: &'input str,
                 let mut repeat_value = vec![];
                 loop {
                     let pos = repeat_pos;
-                    let step_res = parse_pattern_piece::<F>(input, state, pos);
+                    let step_res = parse_pattern_piece(input, state, pos);
                     match step_res {
                         Matched(newpos, value) => {
                             repeat_pos = newpos;
@@ -158,91 +169,82 @@ fn parse_pattern<'input, F: ParserFactory>(input: &'input str,
             };
             match seq_res {
                 Matched(pos, pieces) => {
-                    {
-                        let match_str = &input[start_pos..pos];
-                        Matched(pos, {
-                            let mut pieces = pieces;
-                            utils::flatten_vec(pieces)
-                        })
-                    }
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, {
+                        let mut pieces = pieces;
+                        utils::flatten_vec(pieces)
+                    })
                 }
                 Failed => Failed,
             }
         }
     }
 }
-fn parse_pattern_piece<'input, F: ParserFactory>(input: &'input str,
-                                                 state: &mut ParseState<'input>,
-                                                 pos: usize)
-                                                 -> RuleResult<Vec<TokenType>> {
+fn parse_pattern_piece<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<Vec<PatternPiece<'input>>> {
     {
-        let choice_res = parse_parser_GREEDY::<F>(input, state, pos);
+        let choice_res = parse_parser_GREEDY(input, state, pos);
         match choice_res {
             Matched(pos, value) => Matched(pos, value),
             Failed => {
-                let choice_res = parse_piece_literal::<F>(input, state, pos);
+                let choice_res = parse_piece_literal(input, state, pos);
                 match choice_res {
                     Matched(pos, value) => Matched(pos, value),
-                    Failed => parse_piece_parser::<F>(input, state, pos),
+                    Failed => parse_piece_parser(input, state, pos),
                 }
             }
         }
     }
 }
-fn parse_piece_literal<'input, F: ParserFactory>(input: &'input str,
-                                                 state: &mut ParseState<'input>,
-                                                 pos: usize)
-                                                 -> RuleResult<Vec<TokenType>> {
+fn parse_piece_literal<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<Vec<PatternPiece<'input>>> {
     {
         let start_pos = pos;
         {
-            let seq_res = parse_literal::<F>(input, state, pos);
+            let seq_res = parse_literal(input, state, pos);
             match seq_res {
                 Matched(pos, literal) => {
-                    {
-                        let match_str = &input[start_pos..pos];
-                        Matched(pos, {
-                            let unescaped_literal = grammar::unescape_literal(literal);
-                            vec![TokenType::Literal(unescaped_literal)]
-                        })
-                    }
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, {
+                        let unescaped_literal = grammar::unescape_literal(literal);
+                        vec![PatternPiece::Literal(unescaped_literal)]
+                    })
                 }
                 Failed => Failed,
             }
         }
     }
 }
-fn parse_piece_parser<'input, F: ParserFactory>(input: &'input str,
-                                                state: &mut ParseState<'input>,
-                                                pos: usize)
-                                                -> RuleResult<Vec<TokenType>> {
+fn parse_piece_parser<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<Vec<PatternPiece<'input>>> {
     {
         let start_pos = pos;
         {
-            let seq_res = parse_PARSER_BEGIN::<F>(input, state, pos);
+            let seq_res = parse_PARSER_BEGIN(input, state, pos);
             match seq_res {
                 Matched(pos, _) => {
-                    {
-                        let seq_res = parse_parser::<F>(input, state, pos);
-                        match seq_res {
-                            Matched(pos, parser) => {
-                                {
-                                    let seq_res = parse_PARSER_END::<F>(input, state, pos);
-                                    match seq_res {
-                                        Matched(pos, _) => {
-                                            {
-                                                let match_str = &input[start_pos..pos];
-                                                Matched(pos, {
-                                                    vec![TokenType::Parser(parser)]
-                                                })
-                                            }
-                                        }
-                                        Failed => Failed,
-                                    }
+                    let seq_res = parse_parser(input, state, pos);
+                    match seq_res {
+                        Matched(pos, parser) => {
+                            let seq_res = parse_PARSER_END(input, state, pos);
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, { vec![parser] })
                                 }
+                                Failed => Failed,
                             }
-                            Failed => Failed,
                         }
+                        Failed => Failed,
                     }
                 }
                 Failed => Failed,
@@ -250,111 +252,42 @@ fn parse_piece_parser<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_parser<'input, F: ParserFactory>(input: &'input str,
-                                          state: &mut ParseState<'input>,
-                                          pos: usize)
-                                          -> RuleResult<Box<Parser>> {
-    {
-        let choice_res = parse_parser_SET::<F>(input, state, pos);
-        match choice_res {
-            Matched(pos, value) => Matched(pos, value),
-            Failed => parse_parser_INT::<F>(input, state, pos),
-        }
-    }
-}
-fn parse_parser_SET<'input, F: ParserFactory>(input: &'input str,
-                                              state: &mut ParseState<'input>,
-                                              pos: usize)
-                                              -> RuleResult<Box<Parser>> {
+fn parse_parser<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<PatternPiece<'input>> {
     {
         let start_pos = pos;
         {
-            let seq_res = parse_SET::<F>(input, state, pos);
+            let seq_res = parse_parser_type(input, state, pos);
             match seq_res {
-                Matched(pos, _) => {
-                    {
-                        let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
-                        match seq_res {
-                            Matched(pos, _) => {
-                                {
-                                    let seq_res = parse_string::<F>(input, state, pos);
-                                    match seq_res {
-                                        Matched(pos, set) => {
-                                            {
-                                                let seq_res =
-                                                    match parse_parser_SET_optional_params::<F>(input,
-                                                                                           state,
-                                                                                           pos)
-                                                        {
-                                                        Matched(newpos, value)
-                                                        => {
-                                                            Matched(newpos,
-                                                                    Some(value))
-                                                        }
-                                                        Failed => {
-                                                            Matched(pos, None)
-                                                        }
-                                                    };
-                                                match seq_res {
-                                                    Matched(pos, opt_params) => {
-                                                        {
-                                                            let seq_res =
-                                                                parse_PARSER_PARAMS_END::<F>(input,
-                                                                                             state,
-                                                                                             pos);
-                                                            match seq_res {
-                                                                Matched(pos, _) => {
-                                                                    {
-                                                                        let seq_res =
-                                                                            match parse_parser_name::<F>(input,
-                                                                                                    state,
-                                                                                                    pos)
-                                                                                {
-                                                                                Matched(newpos,
-                                                                                        value)
-                                                                                =>
-                                                                                {
-                                                                                    Matched(newpos,
-                                                                                            Some(value))
-                                                                                }
-                                                                                Failed
-                                                                                =>
-                                                                                {
-                                                                                    Matched(pos,
-                                                                                            None)
-                                                                                }
-                                                                            };
-                                                                        match seq_res {
-                                                                            Matched(pos, name) => {
-                                                                                {
-                                                                                    let match_str =
-                                                                                        &input[start_pos..pos];
-                                                                                    Matched(pos,
-                                                                                            {
-                                                                                                F::new_set(set,
-                                                                                                           name,
-                                                                                                           opt_params)
-                                                                                            })
-                                                                                }
-                                                                            }
-                                                                            Failed => Failed,
-                                                                        }
-                                                                    }
-                                                                }
-                                                                Failed => Failed,
-                                                            }
-                                                        }
-                                                    }
-                                                    Failed => Failed,
-                                                }
-                                            }
-                                        }
-                                        Failed => Failed,
-                                    }
+                Matched(pos, parser_type) => {
+                    let seq_res = match parse_parser_arguments(input, state, pos) {
+                        Matched(newpos, value) => Matched(newpos, Some(value)),
+                        Failed => Matched(pos, None),
+                    };
+                    match seq_res {
+                        Matched(pos, arguments) => {
+                            let seq_res = match parse_parser_name(input, state, pos) {
+                                Matched(newpos, value) => Matched(newpos, Some(value)),
+                                Failed => Matched(pos, None),
+                            };
+                            match seq_res {
+                                Matched(pos, name) => {
+                                    let match_str = &input[start_pos..pos];
+                                    Matched(pos, {
+                                        let params = ParserParams::from_arguments(
+                                            name,
+                                            arguments.unwrap_or_default(),
+                                        );
+                                        PatternPiece::Parser(start_pos, parser_type, params)
+                                    })
                                 }
+                                Failed => Failed,
                             }
-                            Failed => Failed,
                         }
+                        Failed => Failed,
                     }
                 }
                 Failed => Failed,
@@ -362,60 +295,56 @@ fn parse_parser_SET<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_parser_SET_optional_params<'input, F: ParserFactory>
-                                                              (input: &'input str,
-                                                               state: &mut ParseState<'input>,
-                                                               pos: usize)
-                                                               -> RuleResult<Vec<OptionalParameter<'input>>> {
+fn parse_parser_arguments<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<Vec<ParserArgument<'input>>> {
     {
         let start_pos = pos;
         {
-            let seq_res = parse_comma::<F>(input, state, pos);
+            let seq_res = parse_PARSER_PARAMS_BEGIN(input, state, pos);
             match seq_res {
                 Matched(pos, _) => {
-                    {
-                        let seq_res = {
-                            let mut repeat_pos = pos;
-                            let mut repeat_value = vec![];
-                            loop {
-                                let pos = repeat_pos;
-                                let pos = if !repeat_value.is_empty() {
-                                    let sep_res = parse_comma::<F>(input, state, pos);
-                                    match sep_res {
-                                        Matched(newpos, _) => {
-                                            newpos
-                                        }
-                                        Failed => break,
-                                    }
-                                } else {
-                                    pos
-                                };
-                                let step_res = parse_parser_BASE_optional_param::<F>(input,
-                                                                                     state,
-                                                                                     pos);
-                                match step_res {
-                                    Matched(newpos, value) => {
-                                        repeat_pos = newpos;
-                                        repeat_value.push(value);
-                                    }
-                                    Failed => {
-                                        break;
-                                    }
+                    let seq_res = {
+                        let mut repeat_pos = pos;
+                        let mut repeat_value = vec![];
+                        loop {
+                            let pos = repeat_pos;
+                            let pos = if !repeat_value.is_empty() {
+                                let sep_res = parse_comma(input, state, pos);
+                                match sep_res {
+                                    Matched(newpos, _) => newpos,
+                                    Failed => break,
+                                }
+                            } else {
+                                pos
+                            };
+                            let step_res = parse_parser_argument(input, state, pos);
+                            match step_res {
+                                Matched(newpos, value) => {
+                                    repeat_pos = newpos;
+                                    repeat_value.push(value);
+                                }
+                                Failed => {
+                                    break;
                                 }
                             }
-                            Matched(repeat_pos, repeat_value)
-                        };
-                        match seq_res {
-                            Matched(pos, params) => {
-                                {
+                        }
+                        Matched(repeat_pos, repeat_value)
+                    };
+                    match seq_res {
+                        Matched(pos, arguments) => {
+                            let seq_res = parse_PARSER_PARAMS_END(input, state, pos);
+                            match seq_res {
+                                Matched(pos, _) => {
                                     let match_str = &input[start_pos..pos];
-                                    Matched(pos, {
-                                        params
-                                    })
+                                    Matched(pos, { arguments })
                                 }
+                                Failed => Failed,
                             }
-                            Failed => Failed,
                         }
+                        Failed => Failed,
                     }
                 }
                 Failed => Failed,
@@ -423,212 +352,122 @@ fn parse_parser_SET_optional_params<'input, F: ParserFactory>
         }
     }
 }
-fn parse_parser_INT<'input, F: ParserFactory>(input: &'input str,
-                                              state: &mut ParseState<'input>,
-                                              pos: usize)
-                                              -> RuleResult<Box<Parser>> {
+fn parse_parser_argument<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<ParserArgument<'input>> {
     {
-        let start_pos = pos;
-        {
-            let seq_res = parse_INT::<F>(input, state, pos);
-            match seq_res {
-                Matched(pos, _) => {
-                    {
-                        let seq_res = match parse_parser_INT_optional_params::<F>(input,
-                                                                                  state,
-                                                                                  pos) {
-                            Matched(newpos, value) => {
-                                Matched(newpos, Some(value))
-                            }
-                            Failed => {
-                                Matched(pos, None)
-                            }
-                        };
-                        match seq_res {
-                            Matched(pos, opt_params) => {
-                                {
-                                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
-                                        Matched(newpos, value) => {
-                                            Matched(newpos, Some(value))
-                                        }
-                                        Failed => {
-                                            Matched(pos, None)
-                                        }
-                                    };
-                                    match seq_res {
-                                        Matched(pos, name) => {
-                                            {
-                                                let match_str = &input[start_pos..pos];
-                                                Matched(pos, {
-                                                    F::new_int(name, opt_params)
-                                                })
-                                            }
-                                        }
-                                        Failed => Failed,
-                                    }
-                                }
-                            }
-                            Failed => Failed,
-                        }
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = parse_parser_optional_param(input, state, pos);
+                match seq_res {
+                    Matched(pos, param) => {
+                        let match_str = &input[start_pos..pos];
+                        Matched(pos, { ParserArgument::Optional(param) })
                     }
+                    Failed => Failed,
                 }
-                Failed => Failed,
             }
-        }
-    }
-}
-fn parse_parser_INT_optional_params<'input, F: ParserFactory>
-                                                              (input: &'input str,
-                                                               state: &mut ParseState<'input>,
-                                                               pos: usize)
-                                                               -> RuleResult<Vec<OptionalParameter<'input>>> {
-    {
-        let start_pos = pos;
-        {
-            let seq_res = parse_PARSER_PARAMS_BEGIN::<F>(input, state, pos);
-            match seq_res {
-                Matched(pos, _) => {
-                    {
-                        let seq_res = {
-                            let mut repeat_pos = pos;
-                            let mut repeat_value = vec![];
-                            loop {
-                                let pos = repeat_pos;
-                                let pos = if !repeat_value.is_empty() {
-                                    let sep_res = parse_comma::<F>(input, state, pos);
-                                    match sep_res {
-                                        Matched(newpos, _) => {
-                                            newpos
-                                        }
-                                        Failed => break,
-                                    }
-                                } else {
-                                    pos
-                                };
-                                let step_res = parse_parser_BASE_optional_param::<F>(input,
-                                                                                     state,
-                                                                                     pos);
-                                match step_res {
-                                    Matched(newpos, value) => {
-                                        repeat_pos = newpos;
-                                        repeat_value.push(value);
-                                    }
-                                    Failed => {
-                                        break;
-                                    }
-                                }
-                            }
-                            Matched(repeat_pos, repeat_value)
-                        };
-                        match seq_res {
-                            Matched(pos, params) => {
-                                {
-                                    let seq_res = parse_PARSER_PARAMS_END::<F>(input, state, pos);
-                                    match seq_res {
-                                        Matched(pos, _) => {
-                                            {
-                                                let match_str = &input[start_pos..pos];
-                                                Matched(pos, {
-                                                    params
-                                                })
-                                            }
-                                        }
-                                        Failed => Failed,
-                                    }
-                                }
-                            }
-                            Failed => Failed,
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = parse_string(input, state, pos);
+                    match seq_res {
+                        Matched(pos, value) => {
+                            let match_str = &input[start_pos..pos];
+                            Matched(pos, { ParserArgument::Positional(value) })
                         }
+                        Failed => Failed,
                     }
                 }
-                Failed => Failed,
             }
         }
     }
 }
-fn parse_parser_GREEDY<'input, F: ParserFactory>(input: &'input str,
-                                                 state: &mut ParseState<'input>,
-                                                 pos: usize)
-                                                 -> RuleResult<Vec<TokenType>> {
+fn parse_parser_GREEDY<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<Vec<PatternPiece<'input>>> {
     {
         let start_pos = pos;
         {
-            let seq_res = parse_PARSER_BEGIN::<F>(input, state, pos);
+            let seq_res = parse_PARSER_BEGIN(input, state, pos);
             match seq_res {
                 Matched(pos, _) => {
-                    {
-                        let seq_res = parse_GREEDY::<F>(input, state, pos);
-                        match seq_res {
-                            Matched(pos, _) => {
-                                {
-                                    let seq_res = match parse_parser_name::<F>(input, state, pos) {
-                                        Matched(newpos, value) => {
-                                            Matched(newpos, Some(value))
-                                        }
-                                        Failed => {
-                                            Matched(pos, None)
-                                        }
+                    let seq_res = parse_GREEDY(input, state, pos);
+                    match seq_res {
+                        Matched(pos, _) => {
+                            let seq_res = match parse_parser_arguments(input, state, pos) {
+                                Matched(newpos, value) => Matched(newpos, Some(value)),
+                                Failed => Matched(pos, None),
+                            };
+                            match seq_res {
+                                Matched(pos, arguments) => {
+                                    let seq_res = match parse_parser_name(input, state, pos) {
+                                        Matched(newpos, value) => Matched(newpos, Some(value)),
+                                        Failed => Matched(pos, None),
                                     };
                                     match seq_res {
                                         Matched(pos, name) => {
-                                            {
-                                                let seq_res = parse_PARSER_END::<F>(input,
-                                                                                    state,
-                                                                                    pos);
-                                                match seq_res {
-                                                    Matched(pos, _) => {
-                                                        {
-                                                            let seq_res =
-                                                                match parse_literal::<F>(input,
-                                                                                         state,
-                                                                                         pos) {
-                                                                    Matched(newpos, value) => {
-                                                                        Matched(newpos, Some(value))
-                                                                    }
-                                                                    Failed => {
-                                                                        Matched(pos, None)
-                                                                    }
-                                                                };
-                                                            match seq_res {
-                                                                Matched(pos, end_string) => {
-                                                                    {
-                                                                        let match_str =
-                                                                            &input[start_pos..pos];
-                                                                        Matched(pos,
-                                                                                {
-                                                                                    let mut tokens =
-                                                                                        Vec::new();
-                                                                                    let parser =
-                                                                                        F::new_greedy(name,
-                                                                                                      end_string);
-                                                                                    tokens.push(TokenType::Parser(parser));
-                                                                                    let end_string =
-                                                                                        end_string.map(|string|
-                                                                                                           string.to_owned());
-                                                                                    if let Some(end_string)
-                                                                                           =
-                                                                                           end_string
-                                                                                           {
-                                                                                        tokens.push(TokenType::Literal(end_string));
-                                                                                    }
-                                                                                    tokens
-                                                                                })
-                                                                    }
-                                                                }
-                                                                Failed => Failed,
+                                            let seq_res = parse_PARSER_END(input, state, pos);
+                                            match seq_res {
+                                                Matched(pos, _) => {
+                                                    let seq_res =
+                                                        match parse_literal(input, state, pos) {
+                                                            Matched(newpos, value) => {
+                                                                Matched(newpos, Some(value))
                                                             }
+                                                            Failed => Matched(pos, None),
+                                                        };
+                                                    match seq_res {
+                                                        Matched(pos, end_string) => {
+                                                            let match_str = &input[start_pos..pos];
+                                                            Matched(pos, {
+                                                                let mut params =
+                                                                    ParserParams::from_arguments(
+                                                                        name,
+                                                                        arguments
+                                                                            .unwrap_or_default(),
+                                                                    );
+                                                                params.set_end_string(end_string);
+                                                                let mut pieces = Vec::new();
+                                                                pieces.push(PatternPiece::Parser(
+                                                                    start_pos, "GREEDY", params,
+                                                                ));
+                                                                let end_string =
+                                                                    end_string.map(|string| {
+                                                                        string.to_string()
+                                                                    });
+                                                                if let Some(end_string) = end_string
+                                                                {
+                                                                    pieces.push(
+                                                                        PatternPiece::Literal(
+                                                                            end_string,
+                                                                        ),
+                                                                    );
+                                                                }
+                                                                pieces
+                                                            })
                                                         }
+                                                        Failed => Failed,
                                                     }
-                                                    Failed => Failed,
                                                 }
+                                                Failed => Failed,
                                             }
                                         }
                                         Failed => Failed,
                                     }
                                 }
+                                Failed => Failed,
                             }
-                            Failed => Failed,
                         }
+                        Failed => Failed,
                     }
                 }
                 Failed => Failed,
@@ -636,39 +475,31 @@ fn parse_parser_GREEDY<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_parser_BASE_optional_param<'input, F: ParserFactory>
-                                                              (input: &'input str,
-                                                               state: &mut ParseState<'input>,
-                                                               pos: usize)
-                                                               -> RuleResult<OptionalParameter<'input>> {
+fn parse_parser_optional_param<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<OptionalParameter<'input>> {
     {
         let choice_res = {
             let start_pos = pos;
             {
-                let seq_res = parse_MIN_LEN::<F>(input, state, pos);
+                let seq_res = parse_parameter_key(input, state, pos);
                 match seq_res {
-                    Matched(pos, name) => {
-                        {
-                            let seq_res = slice_eq(input, state, pos, "=");
-                            match seq_res {
-                                Matched(pos, _) => {
-                                    {
-                                        let seq_res = parse_int::<F>(input, state, pos);
-                                        match seq_res {
-                                            Matched(pos, value) => {
-                                                {
-                                                    let match_str = &input[start_pos..pos];
-                                                    Matched(pos, {
-                                                        OptionalParameter::Int(name, value)
-                                                    })
-                                                }
-                                            }
-                                            Failed => Failed,
-                                        }
+                    Matched(pos, key) => {
+                        let seq_res = slice_eq(input, state, pos, "=");
+                        match seq_res {
+                            Matched(pos, _) => {
+                                let seq_res = parse_int(input, state, pos);
+                                match seq_res {
+                                    Matched(pos, value) => {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, { OptionalParameter::Int(key, value) })
                                     }
+                                    Failed => Failed,
                                 }
-                                Failed => Failed,
                             }
+                            Failed => Failed,
                         }
                     }
                     Failed => Failed,
@@ -680,30 +511,22 @@ fn parse_parser_BASE_optional_param<'input, F: ParserFactory>
             Failed => {
                 let start_pos = pos;
                 {
-                    let seq_res = parse_MAX_LEN::<F>(input, state, pos);
+                    let seq_res = parse_parameter_key(input, state, pos);
                     match seq_res {
-                        Matched(pos, name) => {
-                            {
-                                let seq_res = slice_eq(input, state, pos, "=");
-                                match seq_res {
-                                    Matched(pos, _) => {
-                                        {
-                                            let seq_res = parse_int::<F>(input, state, pos);
-                                            match seq_res {
-                                                Matched(pos, value) => {
-                                                    {
-                                                        let match_str = &input[start_pos..pos];
-                                                        Matched(pos, {
-                                                            OptionalParameter::Int(name, value)
-                                                        })
-                                                    }
-                                                }
-                                                Failed => Failed,
-                                            }
+                        Matched(pos, key) => {
+                            let seq_res = slice_eq(input, state, pos, "=");
+                            match seq_res {
+                                Matched(pos, _) => {
+                                    let seq_res = parse_string(input, state, pos);
+                                    match seq_res {
+                                        Matched(pos, value) => {
+                                            let match_str = &input[start_pos..pos];
+                                            Matched(pos, { OptionalParameter::Str(key, value) })
                                         }
+                                        Failed => Failed,
                                     }
-                                    Failed => Failed,
                                 }
+                                Failed => Failed,
                             }
                         }
                         Failed => Failed,
@@ -713,43 +536,55 @@ fn parse_parser_BASE_optional_param<'input, F: ParserFactory>
         }
     }
 }
-fn parse_MIN_LEN<'input, F: ParserFactory>(input: &'input str,
-                                           state: &mut ParseState<'input>,
-                                           pos: usize)
-                                           -> RuleResult<&'input str> {
+fn parse_parameter_key<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
-            let seq_res = slice_eq(input, state, pos, "min_len");
-            match seq_res {
-                Matched(pos, _) => {
-                    {
-                        let match_str = &input[start_pos..pos];
-                        Matched(pos, {
-                            match_str
-                        })
-                    }
+            let seq_res = if input.len() > pos {
+                let (ch, next) = char_range_at(input, pos);
+                match ch {
+                    'a'...'z' | '_' => Matched(next, ()),
+                    _ => state.mark_failure(pos, "[a-z_]"),
                 }
-                Failed => Failed,
-            }
-        }
-    }
-}
-fn parse_MAX_LEN<'input, F: ParserFactory>(input: &'input str,
-                                           state: &mut ParseState<'input>,
-                                           pos: usize)
-                                           -> RuleResult<&'input str> {
-    {
-        let start_pos = pos;
-        {
-            let seq_res = slice_eq(input, state, pos, "max_len");
+            } else {
+                state.mark_failure(pos, "[a-z_]")
+            };
             match seq_res {
                 Matched(pos, _) => {
-                    {
-                        let match_str = &input[start_pos..pos];
-                        Matched(pos, {
-                            match_str
-                        })
+                    let seq_res = {
+                        let mut repeat_pos = pos;
+                        loop {
+                            let pos = repeat_pos;
+                            let step_res = if input.len() > pos {
+                                let (ch, next) = char_range_at(input, pos);
+                                match ch {
+                                    'a'...'z' | '0'...'9' | '_' => Matched(next, ()),
+                                    _ => state.mark_failure(pos, "[a-z0-9_]"),
+                                }
+                            } else {
+                                state.mark_failure(pos, "[a-z0-9_]")
+                            };
+                            match step_res {
+                                Matched(newpos, value) => {
+                                    repeat_pos = newpos;
+                                }
+                                Failed => {
+                                    break;
+                                }
+                            }
+                        }
+                        Matched(repeat_pos, ())
+                    };
+                    match seq_res {
+                        Matched(pos, _) => {
+                            let match_str = &input[start_pos..pos];
+                            Matched(pos, { match_str })
+                        }
+                        Failed => Failed,
                     }
                 }
                 Failed => Failed,
@@ -757,43 +592,55 @@ fn parse_MAX_LEN<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_INT<'input, F: ParserFactory>(input: &'input str,
-                                       state: &mut ParseState<'input>,
-                                       pos: usize)
-                                       -> RuleResult<&'input str> {
+fn parse_parser_type<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
-            let seq_res = slice_eq(input, state, pos, "INT");
-            match seq_res {
-                Matched(pos, _) => {
-                    {
-                        let match_str = &input[start_pos..pos];
-                        Matched(pos, {
-                            match_str
-                        })
-                    }
+            let seq_res = if input.len() > pos {
+                let (ch, next) = char_range_at(input, pos);
+                match ch {
+                    'A'...'Z' | '_' => Matched(next, ()),
+                    _ => state.mark_failure(pos, "[A-Z_]"),
                 }
-                Failed => Failed,
-            }
-        }
-    }
-}
-fn parse_SET<'input, F: ParserFactory>(input: &'input str,
-                                       state: &mut ParseState<'input>,
-                                       pos: usize)
-                                       -> RuleResult<&'input str> {
-    {
-        let start_pos = pos;
-        {
-            let seq_res = slice_eq(input, state, pos, "SET");
+            } else {
+                state.mark_failure(pos, "[A-Z_]")
+            };
             match seq_res {
                 Matched(pos, _) => {
-                    {
-                        let match_str = &input[start_pos..pos];
-                        Matched(pos, {
-                            match_str
-                        })
+                    let seq_res = {
+                        let mut repeat_pos = pos;
+                        loop {
+                            let pos = repeat_pos;
+                            let step_res = if input.len() > pos {
+                                let (ch, next) = char_range_at(input, pos);
+                                match ch {
+                                    'A'...'Z' | '0'...'9' | '_' => Matched(next, ()),
+                                    _ => state.mark_failure(pos, "[A-Z0-9_]"),
+                                }
+                            } else {
+                                state.mark_failure(pos, "[A-Z0-9_]")
+                            };
+                            match step_res {
+                                Matched(newpos, value) => {
+                                    repeat_pos = newpos;
+                                }
+                                Failed => {
+                                    break;
+                                }
+                            }
+                        }
+                        Matched(repeat_pos, ())
+                    };
+                    match seq_res {
+                        Matched(pos, _) => {
+                            let match_str = &input[start_pos..pos];
+                            Matched(pos, { match_str })
+                        }
+                        Failed => Failed,
                     }
                 }
                 Failed => Failed,
@@ -801,75 +648,71 @@ fn parse_SET<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_GREEDY<'input, F: ParserFactory>(input: &'input str,
-                                          state: &mut ParseState<'input>,
-                                          pos: usize)
-                                          -> RuleResult<&'input str> {
+fn parse_GREEDY<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
             let seq_res = slice_eq(input, state, pos, "GREEDY");
             match seq_res {
                 Matched(pos, _) => {
-                    {
-                        let match_str = &input[start_pos..pos];
-                        Matched(pos, {
-                            match_str
-                        })
-                    }
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
                 }
                 Failed => Failed,
             }
         }
     }
 }
-fn parse_PARSER_BEGIN<'input, F: ParserFactory>(input: &'input str,
-                                                state: &mut ParseState<'input>,
-                                                pos: usize)
-                                                -> RuleResult<()> {
+fn parse_PARSER_BEGIN<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<()> {
     slice_eq(input, state, pos, "%{")
 }
-fn parse_PARSER_END<'input, F: ParserFactory>(input: &'input str,
-                                              state: &mut ParseState<'input>,
-                                              pos: usize)
-                                              -> RuleResult<()> {
+fn parse_PARSER_END<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<()> {
     slice_eq(input, state, pos, "}")
 }
-fn parse_PARSER_PARAMS_BEGIN<'input, F: ParserFactory>(input: &'input str,
-                                                       state: &mut ParseState<'input>,
-                                                       pos: usize)
-                                                       -> RuleResult<()> {
+fn parse_PARSER_PARAMS_BEGIN<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<()> {
     slice_eq(input, state, pos, "(")
 }
-fn parse_PARSER_PARAMS_END<'input, F: ParserFactory>(input: &'input str,
-                                                     state: &mut ParseState<'input>,
-                                                     pos: usize)
-                                                     -> RuleResult<()> {
+fn parse_PARSER_PARAMS_END<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<()> {
     slice_eq(input, state, pos, ")")
 }
-fn parse_parser_name<'input, F: ParserFactory>(input: &'input str,
-                                               state: &mut ParseState<'input>,
-                                               pos: usize)
-                                               -> RuleResult<&'input str> {
+fn parse_parser_name<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
             let seq_res = slice_eq(input, state, pos, ":");
             match seq_res {
                 Matched(pos, _) => {
-                    {
-                        let seq_res = parse_identifier::<F>(input, state, pos);
-                        match seq_res {
-                            Matched(pos, name) => {
-                                {
-                                    let match_str = &input[start_pos..pos];
-                                    Matched(pos, {
-                                        name
-                                    })
-                                }
-                            }
-                            Failed => Failed,
+                    let seq_res = parse_identifier(input, state, pos);
+                    match seq_res {
+                        Matched(pos, name) => {
+                            let match_str = &input[start_pos..pos];
+                            Matched(pos, { name })
                         }
+                        Failed => Failed,
                     }
                 }
                 Failed => Failed,
@@ -877,10 +720,11 @@ fn parse_parser_name<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_identifier<'input, F: ParserFactory>(input: &'input str,
-                                              state: &mut ParseState<'input>,
-                                              pos: usize)
-                                              -> RuleResult<&'input str> {
+fn parse_identifier<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
@@ -895,69 +739,58 @@ fn parse_identifier<'input, F: ParserFactory>(input: &'input str,
             };
             match seq_res {
                 Matched(pos, _) => {
-                    {
-                        let seq_res = {
-                            let mut repeat_pos = pos;
-                            loop {
-                                let pos = repeat_pos;
-                                let step_res = {
-                                    let seq_res = if input.len() > pos {
-                                        let (ch, next) = char_range_at(input, pos);
-                                        match ch {
-                                            'a'...'z' |
-                                            '-' |
-                                            'A'...'Z' |
-                                            '0'...'9' |
-                                            '_' |
-                                            '.' => Matched(next, ()),
-                                            _ => state.mark_failure(pos, "[a-z-A-Z0-9_.]"),
+                    let seq_res = {
+                        let mut repeat_pos = pos;
+                        loop {
+                            let pos = repeat_pos;
+                            let step_res = {
+                                let seq_res = if input.len() > pos {
+                                    let (ch, next) = char_range_at(input, pos);
+                                    match ch {
+                                        'a'...'z' | '-' | 'A'...'Z' | '0'...'9' | '_' | '.' => {
+                                            Matched(next, ())
                                         }
-                                    } else {
-                                        state.mark_failure(pos, "[a-z-A-Z0-9_.]")
-                                    };
-                                    match seq_res {
-                                        Matched(pos, _) => {
-                                            {
-                                                let assert_res = if input.len() > pos {
-                                                    let (ch, next) = char_range_at(input, pos);
-                                                    match ch {
-                                                        '-' => Matched(next, ()),
-                                                        _ => state.mark_failure(pos, "[-]"),
-                                                    }
-                                                } else {
-                                                    state.mark_failure(pos, "[-]")
-                                                };
-                                                match assert_res {
-                                                    Failed => Matched(pos, ()),
-                                                    Matched(..) => Failed,
-                                                }
-                                            }
-                                        }
-                                        Failed => Failed,
+                                        _ => state.mark_failure(pos, "[a-z-A-Z0-9_.]"),
                                     }
+                                } else {
+                                    state.mark_failure(pos, "[a-z-A-Z0-9_.]")
                                 };
-                                match step_res {
-                                    Matched(newpos, value) => {
-                                        repeat_pos = newpos;
-                                    }
-                                    Failed => {
-                                        break;
+                                match seq_res {
+                                    Matched(pos, _) => {
+                                        let assert_res = if input.len() > pos {
+                                            let (ch, next) = char_range_at(input, pos);
+                                            match ch {
+                                                '-' => Matched(next, ()),
+                                                _ => state.mark_failure(pos, "[-]"),
+                                            }
+                                        } else {
+                                            state.mark_failure(pos, "[-]")
+                                        };
+                                        match assert_res {
+                                            Failed => Matched(pos, ()),
+                                            Matched(..) => Failed,
+                                        }
                                     }
+                                    Failed => Failed,
                                 }
-                            }
-                            Matched(repeat_pos, ())
-                        };
-                        match seq_res {
-                            Matched(pos, _) => {
-                                {
-                                    let match_str = &input[start_pos..pos];
-                                    Matched(pos, {
-                                        match_str
-                                    })
+                            };
+                            match step_res {
+                                Matched(newpos, value) => {
+                                    repeat_pos = newpos;
+                                }
+                                Failed => {
+                                    break;
                                 }
                             }
-                            Failed => Failed,
                         }
+                        Matched(repeat_pos, ())
+                    };
+                    match seq_res {
+                        Matched(pos, _) => {
+                            let match_str = &input[start_pos..pos];
+                            Matched(pos, { match_str })
+                        }
+                        Failed => Failed,
                     }
                 }
                 Failed => Failed,
@@ -965,48 +798,72 @@ fn parse_identifier<'input, F: ParserFactory>(input: &'input str,
         }
     }
 }
-fn parse_string<'input, F: ParserFactory>(input: &'input str,
-                                          state: &mut ParseState<'input>,
-                                          pos: usize)
-                                          -> RuleResult<&'input str> {
+fn parse_string<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<&'input str> {
     {
-        let start_pos = pos;
-        {
-            let seq_res = slice_eq(input, state, pos, "\"");
-            match seq_res {
-                Matched(pos, _) => {
-                    {
-                        let seq_res = parse_all_chars_until_quotation_mark::<F>(input, state, pos);
+        let choice_res = {
+            let start_pos = pos;
+            {
+                let seq_res = slice_eq(input, state, pos, "\"");
+                match seq_res {
+                    Matched(pos, _) => {
+                        let seq_res = parse_all_chars_until_quotation_mark(input, state, pos);
                         match seq_res {
                             Matched(pos, s) => {
-                                {
-                                    let seq_res = slice_eq(input, state, pos, "\"");
+                                let seq_res = slice_eq(input, state, pos, "\"");
+                                match seq_res {
+                                    Matched(pos, _) => {
+                                        let match_str = &input[start_pos..pos];
+                                        Matched(pos, { s })
+                                    }
+                                    Failed => Failed,
+                                }
+                            }
+                            Failed => Failed,
+                        }
+                    }
+                    Failed => Failed,
+                }
+            }
+        };
+        match choice_res {
+            Matched(pos, value) => Matched(pos, value),
+            Failed => {
+                let start_pos = pos;
+                {
+                    let seq_res = slice_eq(input, state, pos, "'");
+                    match seq_res {
+                        Matched(pos, _) => {
+                            let seq_res = parse_all_chars_until_apostrophe(input, state, pos);
+                            match seq_res {
+                                Matched(pos, s) => {
+                                    let seq_res = slice_eq(input, state, pos, "'");
                                     match seq_res {
                                         Matched(pos, _) => {
-                                            {
-                                                let match_str = &input[start_pos..pos];
-                                                Matched(pos, {
-                                                    s
-                                                })
-                                            }
+                                            let match_str = &input[start_pos..pos];
+                                            Matched(pos, { s })
                                         }
                                         Failed => Failed,
                                     }
                                 }
+                                Failed => Failed,
                             }
-                            Failed => Failed,
                         }
+                        Failed => Failed,
                     }
                 }
-                Failed => Failed,
             }
         }
     }
 }
-fn parse_literal<'input, F: ParserFactory>(input: &'input str,
-                                           state: &mut ParseState<'input>,
-                                           pos: usize)
-                                           -> RuleResult<&'input str> {
+fn parse_literal<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
@@ -1017,16 +874,14 @@ fn parse_literal<'input, F: ParserFactory>(input: &'input str,
                     let pos = repeat_pos;
                     let step_res = {
                         let seq_res = {
-                            let assert_res = parse_PARSER_BEGIN::<F>(input, state, pos);
+                            let assert_res = parse_PARSER_BEGIN(input, state, pos);
                             match assert_res {
                                 Failed => Matched(pos, ()),
                                 Matched(..) => Failed,
                             }
                         };
                         match seq_res {
-                            Matched(pos, _) => {
-                                any_char(input, state, pos)
-                            }
+                            Matched(pos, _) => any_char(input, state, pos),
                             Failed => Failed,
                         }
                     };
@@ -1048,28 +903,24 @@ fn parse_literal<'input, F: ParserFactory>(input: &'input str,
             };
             match seq_res {
                 Matched(pos, _) => {
-                    {
-                        let match_str = &input[start_pos..pos];
-                        Matched(pos, {
-                            match_str
-                        })
-                    }
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
                 }
                 Failed => Failed,
             }
         }
     }
 }
-fn parse_all_chars_until_quotation_mark<'input, F: ParserFactory>(input: &'input str,
-                                                                  state: &mut ParseState<'input>,
-                                                                  pos: usize)
-                                                                  -> RuleResult<&'input str> {
+fn parse_all_chars_until_quotation_mark<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<&'input str> {
     {
         let start_pos = pos;
         {
             let seq_res = {
                 let mut repeat_pos = pos;
-                let mut repeat_value = vec![];
                 loop {
                     let pos = repeat_pos;
                     let step_res = {
@@ -1081,75 +932,110 @@ fn parse_all_chars_until_quotation_mark<'input, F: ParserFactory>(input: &'input
                             }
                         };
                         match seq_res {
-                            Matched(pos, _) => {
-                                any_char(input, state, pos)
-                            }
+                            Matched(pos, _) => any_char(input, state, pos),
                             Failed => Failed,
                         }
                     };
                     match step_res {
                         Matched(newpos, value) => {
                             repeat_pos = newpos;
-                            repeat_value.push(value);
                         }
                         Failed => {
                             break;
                         }
                     }
                 }
-                if repeat_value.len() >= 1usize {
-                    Matched(repeat_pos, ())
-                } else {
-                    Failed
-                }
+                Matched(repeat_pos, ())
             };
             match seq_res {
                 Matched(pos, _) => {
-                    {
-                        let match_str = &input[start_pos..pos];
-                        Matched(pos, {
-                            match_str
-                        })
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
+                Failed => Failed,
+            }
+        }
+    }
+}
+fn parse_all_chars_until_apostrophe<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<&'input str> {
+    {
+        let start_pos = pos;
+        {
+            let seq_res = {
+                let mut repeat_pos = pos;
+                loop {
+                    let pos = repeat_pos;
+                    let step_res = {
+                        let seq_res = {
+                            let assert_res = slice_eq(input, state, pos, "'");
+                            match assert_res {
+                                Failed => Matched(pos, ()),
+                                Matched(..) => Failed,
+                            }
+                        };
+                        match seq_res {
+                            Matched(pos, _) => any_char(input, state, pos),
+                            Failed => Failed,
+                        }
+                    };
+                    match step_res {
+                        Matched(newpos, value) => {
+                            repeat_pos = newpos;
+                        }
+                        Failed => {
+                            break;
+                        }
                     }
                 }
+                Matched(repeat_pos, ())
+            };
+            match seq_res {
+                Matched(pos, _) => {
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { match_str })
+                }
                 Failed => Failed,
             }
         }
     }
 }
-fn parse_comma<'input, F: ParserFactory>(input: &'input str,
-                                         state: &mut ParseState<'input>,
-                                         pos: usize)
-                                         -> RuleResult<()> {
+fn parse_comma<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<()> {
     {
         let seq_res = slice_eq(input, state, pos, ",");
         match seq_res {
             Matched(pos, _) => {
-                {
-                    let mut repeat_pos = pos;
-                    loop {
-                        let pos = repeat_pos;
-                        let step_res = slice_eq(input, state, pos, " ");
-                        match step_res {
-                            Matched(newpos, value) => {
-                                repeat_pos = newpos;
-                            }
-                            Failed => {
-                                break;
-                            }
+                let mut repeat_pos = pos;
+                loop {
+                    let pos = repeat_pos;
+                    let step_res = slice_eq(input, state, pos, " ");
+                    match step_res {
+                        Matched(newpos, value) => {
+                            repeat_pos = newpos;
+                        }
+                        Failed => {
+                            break;
                         }
                     }
-                    Matched(repeat_pos, ())
                 }
+                Matched(repeat_pos, ())
             }
             Failed => Failed,
         }
     }
 }
-fn parse_int<'input, F: ParserFactory>(input: &'input str,
-                                       state: &mut ParseState<'input>,
-                                       pos: usize)
-                                       -> RuleResult<usize> {
+fn parse_int<'input>(
+    input: &'input str,
+    state: &mut ParseState<'input>,
+    pos: usize,
+) -> RuleResult<usize> {
     {
         let start_pos = pos;
         {
@@ -1185,21 +1071,17 @@ fn parse_int<'input, F: ParserFactory>(input: &'input str,
             };
             match seq_res {
                 Matched(pos, _) => {
-                    {
-                        let match_str = &input[start_pos..pos];
-                        Matched(pos, {
-                            usize::from_str(match_str).ok().unwrap()
-                        })
-                    }
+                    let match_str = &input[start_pos..pos];
+                    Matched(pos, { usize::from_str(match_str).ok().unwrap() })
                 }
                 Failed => Failed,
             }
         }
     }
 }
-pub fn pattern<F: ParserFactory>(input: &str) -> ParseResult<CompiledPattern> {
+pub fn pattern<'input>(input: &'input str) -> ParseResult<Vec<PatternPiece<'input>>> {
     let mut state = ParseState::new();
-    if let Matched(pos, value) = parse_pattern::<F>(input, &mut state, 0) {
+    if let Matched(pos, value) = parse_pattern(input, &mut state, 0) {
         if pos == input.len() {
             return Ok(value);
         }
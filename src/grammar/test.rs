@@ -60,6 +60,17 @@ fn test_given_parser_as_a_string_when_its_type_isnt_exist_then_we_get_an_error()
     ::grammar::parser::pattern(string_parser_with_invalid_type).err().unwrap();
 }
 
+#[test]
+fn test_given_pattern_when_a_parser_cannot_be_created_then_the_error_points_to_the_parser() {
+    let err = ::grammar::parser::pattern("foo\nbar %{INVALID:test_name}").err().unwrap();
+    assert_eq!(err.offset, 10);
+    assert_eq!(err.line, 2);
+    assert_eq!(err.column, 7);
+    assert_eq!(err.expected.contains("known parser type with valid parameters"), true);
+    let err = ::grammar::parser::pattern("%{GREEDY(mode=\"foo\")}").err().unwrap();
+    assert_eq!(err.offset, 0);
+}
+
 
 #[test]
 fn test_given_literal_as_a_string_when_it_is_parsed_then_we_stop_at_the_parsers_begin() {
@@ -210,3 +221,150 @@ fn test_given_valid_pattern_when_it_does_not_have_a_name_then_we_can_parse_the_p
     println!("{:?}", &vec);
     assert_parser_name_equals(vec.get(0), None);
 }
+
+#[test]
+fn test_given_custom_parser_when_it_is_registered_then_the_grammar_resolves_it_through_the_registry
+    () {
+    use parsers::{ParserRegistry, ParserParams};
+
+    let mut registry = ParserRegistry::default();
+    registry.register("HEX", |params: ParserParams| {
+        try!(params.expect_positional(0));
+        try!(params.expect_known_keys(&["max_len"]));
+        let mut parser = SetParser::new("0123456789abcdef");
+        parser.set_max_length(try!(params.int("max_len")));
+        parser.set_name(params.name().map(|name| name.to_owned()));
+        Ok(Box::new(parser) as Box<Parser>)
+    });
+    let mut expected_parser = SetParser::from_str("id", "0123456789abcdef");
+    expected_parser.set_max_length(Some(8));
+
+    let vec = ::grammar::parser::pattern_with_registry("id=%{HEX(max_len=8):id}", &registry)
+                  .expect("Failed to resolve a registered parser type");
+    assert_eq!(vec.len(), 2);
+    assert_parser_equals(vec.get(1), &expected_parser);
+    ::grammar::parser::pattern("id=%{HEX(max_len=8):id}").err().unwrap();
+}
+
+#[test]
+fn test_given_registered_parser_when_its_parameters_are_invalid_then_we_get_an_error() {
+    ::grammar::parser::pattern(r#"%{INT("0123"):test_int}"#).err().unwrap();
+    ::grammar::parser::pattern(r#"%{INT(foo=2):test_int}"#).err().unwrap();
+    ::grammar::parser::pattern(r#"%{SET(min_len=2):test_set}"#).err().unwrap();
+}
+
+#[test]
+fn test_given_parser_when_a_parameter_which_cannot_be_empty_is_empty_then_we_get_an_error() {
+    ::grammar::parser::pattern(r#"%{SET(""):test_set}"#).err().unwrap();
+    ::grammar::parser::pattern(r#"%{ICASE(''):test_icase}"#).err().unwrap();
+    ::grammar::parser::pattern(r#"%{ESTRING(""):test_estring}"#).err().unwrap();
+    ::grammar::parser::pattern(r#"%{TIMESTAMP(""):test_timestamp}"#).err().unwrap();
+    ::grammar::parser::pattern(r#"%{KV(kv_separator=""):test_kv}"#).err().unwrap();
+    ::grammar::parser::pattern(r#"%{CSV("a", "", "b"):test_csv}"#).ok().unwrap();
+    ::grammar::parser::pattern(r#"%{KV(quote=""):test_kv}"#).ok().unwrap();
+}
+
+#[test]
+fn test_given_string_parameter_when_it_is_quoted_with_apostrophes_then_it_can_contain_quotation_marks
+    () {
+    let expected_parser = SetParser::from_str("test_set", "\"");
+    let vec = ::grammar::parser::pattern(r#"%{SET('"'):test_set}"#).ok().unwrap();
+    assert_eq!(vec.len(), 1);
+    assert_parser_equals(vec.get(0), &expected_parser);
+}
+
+#[test]
+fn test_given_kv_parser_when_we_parse_it_then_its_parameters_are_used() {
+    let vec = ::grammar::parser::pattern(r#"fw: %{KV(pair_separator=";", quote="'", prefix=".fw."):kv}"#)
+                  .expect("Failed to parse a KV parser");
+    assert_eq!(vec.len(), 2);
+
+    if let TokenType::Parser(ref parser) = *vec.get(1).unwrap() {
+        let res = parser.parse("a=1;b='2;3'").unwrap();
+        assert_eq!(res.value(), "a=1;b='2;3'");
+        assert_eq!(res.into_values(),
+                   vec![(".fw.a".to_owned(), "1".to_owned()), (".fw.b".to_owned(), "2;3".to_owned())]);
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn test_given_json_parser_when_we_parse_it_then_its_parameters_are_used() {
+    let vec = ::grammar::parser::pattern(r#"app[%{INT}]: %{JSON(max_depth=1, arrays="json"):.json}"#)
+                  .expect("Failed to parse a JSON parser");
+    assert_eq!(vec.len(), 4);
+
+    if let TokenType::Parser(ref parser) = *vec.get(3).unwrap() {
+        let res = parser.parse(r#"{"user":"x","tags":["a"]}"#).unwrap();
+        assert_eq!(res.into_values(),
+                   vec![(".json.tags".to_owned(), r#"["a"]"#.to_owned()),
+                        (".json.user".to_owned(), "x".to_owned())]);
+    } else {
+        unreachable!();
+    }
+}
+
+#[test]
+fn test_given_csv_parser_when_it_is_parsed_then_the_column_names_are_its_positional_arguments() {
+    let vec = ::grammar::parser::pattern(r#"%{CSV("vendor", "", "product", delimiter="|"):cef}"#)
+                  .expect("Failed to parse a CSV parser");
+    assert_eq!(vec.len(), 1);
+
+    if let TokenType::Parser(ref parser) = *vec.get(0).unwrap() {
+        let res = parser.parse("Big Co|x|fw").unwrap();
+        assert_eq!(res.into_values(),
+                   vec![("cef.vendor".to_owned(), "Big Co".to_owned()),
+                        ("cef.product".to_owned(), "fw".to_owned())]);
+    } else {
+        unreachable!();
+    }
+    assert_eq!(::grammar::parser::pattern("%{CSV(delimiter=';')}").is_err(), true);
+}
+
+#[test]
+fn test_given_timestamp_parsers_when_they_are_parsed_then_they_take_a_format_and_normalize_the_value
+    () {
+    let vec = ::grammar::parser::pattern(r#"%{BSD_TIMESTAMP(year=2016, timezone="+02:00", normalize=".ts"):date} %{TIMESTAMP("%Y/%m/%d")}"#)
+                  .expect("Failed to parse timestamp parsers");
+    assert_eq!(vec.len(), 3);
+
+    if let TokenType::Parser(ref parser) = *vec.get(0).unwrap() {
+        let res = parser.parse("Jun 25 14:09:58 2016/06/25").unwrap();
+        assert_eq!(res.value(), "Jun 25 14:09:58");
+        assert_eq!(res.into_values(),
+                   vec![(".ts".to_owned(), "2016-06-25T14:09:58+02:00".to_owned())]);
+    } else {
+        unreachable!();
+    }
+    if let TokenType::Parser(ref parser) = *vec.get(2).unwrap() {
+        assert_eq!(parser.parse("2016/06/25").unwrap().value(), "2016/06/25");
+    } else {
+        unreachable!();
+    }
+    assert_eq!(::grammar::parser::pattern("%{TIMESTAMP}").is_err(), true);
+    assert_eq!(::grammar::parser::pattern(r#"%{ISO_TIMESTAMP(timezone="CET")}"#).is_err(), true);
+}
+
+#[test]
+fn test_given_parsers_when_their_syntax_is_written_then_it_is_parsed_to_the_same_parsers() {
+    for input in &["%{SET(\"abc\",max_len=3,min_len=1):name}",
+                   "%{INT(max_len=5):pid}",
+                   "%{GREEDY(mode=\"successive\"):msg}",
+                   "%{ESTRING(\"'\",consume=\"false\"):user}",
+                   "%{ICASE(\"error\")}",
+                   "%{INT(type=\"float\"):ratio}",
+                   "%{KV(kv_separator=\":\",pair_separator=\";\",prefix=\"kv.\",quote=\"\")}",
+                   "%{JSON(arrays=\"skip\",max_depth=2):doc}",
+                   "%{CSV(\"a\",\"\",\"c\",delimiter=\";\"):row}",
+                   "%{TIMESTAMP(\"%Y-%m-%d\",normalize=\"ts\",timezone=\"-05:30\",year=2016):date}"] {
+        let pattern = ::grammar::parser::pattern(input).unwrap();
+        if let Some(&TokenType::Parser(ref parser)) = pattern.get(0) {
+            let syntax = parser.syntax().unwrap().to_string();
+            assert_eq!(&syntax, input);
+            assert_parser_equals(::grammar::parser::pattern(&syntax).unwrap().get(0), &**parser);
+        } else {
+            unreachable!();
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use matcher::Matcher;
+use matcher::result::MatchResult;
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+// these characters always form their own tokens, the others are split by whitespace
+const DELIMITERS: &'static str = ",;=()[]{}<>\"'|";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TokenKind {
+    Whitespace,
+    Delimiter,
+    Ip,
+    Int,
+    Hex,
+    Word,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Token<'a> {
+    text: &'a str,
+    kind: TokenKind,
+}
+
+impl<'a> Token<'a> {
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn kind(&self) -> TokenKind {
+        self.kind
+    }
+
+    // The variable parts of the token are replaced by placeholders, every whitespace
+    // run has the same shape.
+    pub fn shape(&self) -> String {
+        match self.kind {
+            TokenKind::Whitespace => " ".to_owned(),
+            TokenKind::Delimiter => self.text.to_owned(),
+            TokenKind::Ip => "<IP>".to_owned(),
+            TokenKind::Int => "<INT>".to_owned(),
+            TokenKind::Hex => "<HEX>".to_owned(),
+            TokenKind::Word => replace_digits(self.text),
+        }
+    }
+}
+
+fn replace_digits(word: &str) -> String {
+    let mut shape = String::new();
+    let mut in_digits = false;
+    for c in word.chars() {
+        if c.is_digit(10) {
+            if !in_digits {
+                shape.push_str("<INT>");
+            }
+            in_digits = true;
+        } else {
+            shape.push(c);
+            in_digits = false;
+        }
+    }
+    shape
+}
+
+fn is_delimiter(c: char) -> bool {
+    DELIMITERS.contains(c)
+}
+
+fn classify(word: &str) -> TokenKind {
+    if word.chars().all(|c| c.is_digit(10)) {
+        TokenKind::Int
+    } else if is_ip(word) {
+        TokenKind::Ip
+    } else if is_hex(word) {
+        TokenKind::Hex
+    } else {
+        TokenKind::Word
+    }
+}
+
+fn is_ip(word: &str) -> bool {
+    Ipv4Addr::from_str(word).is_ok() ||
+    (word.chars().any(|c| c.is_digit(16)) && Ipv6Addr::from_str(word).is_ok())
+}
+
+// the hex values without a `0x` prefix have to contain a digit and a letter, otherwise
+// numbers and words like `face` would be hex values
+fn is_hex(word: &str) -> bool {
+    if word.starts_with("0x") || word.starts_with("0X") {
+        word.len() > 2 && word[2..].chars().all(|c| c.is_digit(16))
+    } else {
+        word.chars().all(|c| c.is_digit(16)) && word.chars().any(|c| c.is_digit(10)) &&
+        word.chars().any(|c| !c.is_digit(10))
+    }
+}
+
+// Splits the message into whitespace runs, delimiter characters and the words between
+// them. Concatenating the texts of the tokens gives back the message.
+pub fn tokenize(message: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut chars = message.char_indices().peekable();
+    while let Some((begin, c)) = chars.next() {
+        let kind = if c.is_whitespace() {
+            TokenKind::Whitespace
+        } else if is_delimiter(c) {
+            TokenKind::Delimiter
+        } else {
+            TokenKind::Word
+        };
+        let mut end = begin + c.len_utf8();
+        if kind != TokenKind::Delimiter {
+            while let Some(&(index, next)) = chars.peek() {
+                let same_kind = if kind == TokenKind::Whitespace {
+                    next.is_whitespace()
+                } else {
+                    !next.is_whitespace() && !is_delimiter(next)
+                };
+                if !same_kind {
+                    break;
+                }
+                end = index + next.len_utf8();
+                chars.next();
+            }
+        }
+        let text = &message[start..end];
+        let kind = if kind == TokenKind::Word {
+            classify(text)
+        } else {
+            kind
+        };
+        tokens.push(Token {
+            text: text,
+            kind: kind,
+        });
+        start = end;
+    }
+    tokens
+}
+
+pub fn shape(message: &str) -> String {
+    tokenize(message).iter().map(|token| token.shape()).collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cluster {
+    shape: String,
+    count: usize,
+    examples: Vec<String>,
+}
+
+impl Cluster {
+    fn new(shape: String) -> Cluster {
+        Cluster {
+            shape: shape,
+            count: 0,
+            examples: Vec::new(),
+        }
+    }
+
+    pub fn shape(&self) -> &str {
+        &self.shape
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn examples(&self) -> &[String] {
+        &self.examples
+    }
+}
+
+// Groups the unmatched messages by their shapes. Only the first max_examples
+// different messages of a cluster are kept, so the memory usage depends on the
+// number of clusters and not on the number of messages.
+#[derive(Clone, Debug)]
+pub struct Clusterer {
+    clusters: HashMap<String, Cluster>,
+    max_examples: usize,
+    messages: usize,
+}
+
+impl Clusterer {
+    pub fn new(max_examples: usize) -> Clusterer {
+        Clusterer {
+            clusters: HashMap::new(),
+            max_examples: max_examples,
+            messages: 0,
+        }
+    }
+
+    pub fn add(&mut self, message: &str) {
+        self.messages += 1;
+        let shape = shape(message);
+        let max_examples = self.max_examples;
+        let cluster = self.clusters.entry(shape.clone()).or_insert_with(|| Cluster::new(shape));
+        cluster.count += 1;
+        if cluster.examples.len() < max_examples &&
+           !cluster.examples.iter().any(|example| example == message) {
+            cluster.examples.push(message.to_owned());
+        }
+    }
+
+    // parses the message and adds it to the clusters if it doesn't match
+    pub fn parse<'a, 'b, M>(&mut self, matcher: &'a M, message: &'b str) -> Option<MatchResult<'a, 'b>>
+        where M: Matcher + ?Sized
+    {
+        let result = matcher.parse(message);
+        if result.is_none() {
+            self.add(message);
+        }
+        result
+    }
+
+    // the number of added messages
+    pub fn messages(&self) -> usize {
+        self.messages
+    }
+
+    pub fn len(&self) -> usize {
+        self.clusters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+
+    // the n largest clusters, the clusters with the same size are ordered by their shapes
+    pub fn largest(&self, n: usize) -> Vec<&Cluster> {
+        let mut clusters: Vec<&Cluster> = self.clusters.values().collect();
+        clusters.sort_by(|a, b| {
+            match b.count.cmp(&a.count) {
+                Ordering::Equal => a.shape.cmp(&b.shape),
+                ordering => ordering,
+            }
+        });
+        clusters.truncate(n);
+        clusters
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Clusterer, shape};
+    use matcher::Pattern;
+    use matcher::trie::SuffixTree;
+    use matcher::Matcher;
+
+    #[test]
+    fn test_given_message_when_its_shape_is_created_then_the_variable_tokens_are_replaced_by_placeholders
+        () {
+        assert_eq!(shape("Accepted password for root from 192.168.0.1 port 22 ssh2"),
+                   "Accepted password for root from <IP> port <INT> ssh<INT>");
+        assert_eq!(shape("session=0x1f  id=deadbeef01,ip=fe80::1"),
+                   "session=<HEX> id=<HEX>,ip=<IP>");
+    }
+
+    #[test]
+    fn test_given_clusterer_when_unmatched_messages_are_added_then_the_largest_clusters_are_reported
+        () {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern("user %{INT:uid} logged in").unwrap());
+        let mut matcher = SuffixTree::new();
+        matcher.add_pattern(pattern);
+
+        let mut clusterer = Clusterer::new(2);
+        for message in &["user 1 logged in",
+                         "connection from 10.0.0.1 closed",
+                         "connection from 10.0.0.2 closed",
+                         "connection from 10.0.0.2 closed",
+                         "connection from 10.0.0.3 closed",
+                         "disk sda1 is full"] {
+            clusterer.parse(&matcher, message);
+        }
+
+        assert_eq!(clusterer.messages(), 5);
+        assert_eq!(clusterer.len(), 2);
+        let largest = clusterer.largest(1);
+        assert_eq!(largest.len(), 1);
+        assert_eq!(largest[0].shape(), "connection from <IP> closed");
+        assert_eq!(largest[0].count(), 4);
+        assert_eq!(largest[0].examples(),
+                   &["connection from 10.0.0.1 closed".to_owned(),
+                     "connection from 10.0.0.2 closed".to_owned()][..]);
+    }
+}
@@ -0,0 +1,327 @@
+use serde_json::{self, Value};
+use serde_yaml;
+use uuid::Uuid;
+
+use learn::cluster::{tokenize, Token, TokenKind};
+use parsers::ParserSyntax;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+// the characters which make a variable token free text instead of a SET
+const SET_PUNCTUATION: &'static str = "._-:/@+";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestMessageProposal {
+    message: String,
+    values: BTreeMap<String, String>,
+}
+
+impl TestMessageProposal {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn values(&self) -> &BTreeMap<String, String> {
+        &self.values
+    }
+
+    fn to_value(&self) -> Value {
+        let mut object = BTreeMap::new();
+        object.insert("message".to_owned(), Value::String(self.message.clone()));
+        let values = self.values
+                         .iter()
+                         .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+                         .collect();
+        object.insert("values".to_owned(), Value::Object(values));
+        Value::Object(object)
+    }
+}
+
+// A pattern learnt from the lines with the same structure. The number of these lines
+// is its support.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Proposal {
+    uuid: Uuid,
+    pattern: String,
+    support: usize,
+    test_messages: Vec<TestMessageProposal>,
+}
+
+impl Proposal {
+    pub fn uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn support(&self) -> usize {
+        self.support
+    }
+
+    pub fn test_messages(&self) -> &[TestMessageProposal] {
+        &self.test_messages
+    }
+
+    // a pattern object of a pattern file
+    pub fn to_value(&self) -> Value {
+        let mut object = BTreeMap::new();
+        object.insert("uuid".to_owned(),
+                      Value::String(self.uuid.hyphenated().to_string()));
+        object.insert("pattern".to_owned(), Value::String(self.pattern.clone()));
+        let test_messages = self.test_messages.iter().map(|message| message.to_value()).collect();
+        object.insert("test_messages".to_owned(), Value::Array(test_messages));
+        Value::Object(object)
+    }
+}
+
+// the proposals as a pattern file which can be read by PatternLoader
+pub fn to_pattern_file(proposals: &[Proposal]) -> Value {
+    let patterns = proposals.iter().map(|proposal| proposal.to_value()).collect();
+    let mut object = BTreeMap::new();
+    object.insert("patterns".to_owned(), Value::Array(patterns));
+    Value::Object(object)
+}
+
+pub fn to_json(proposals: &[Proposal]) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&to_pattern_file(proposals))
+}
+
+pub fn to_yaml(proposals: &[Proposal]) -> Result<String, serde_yaml::Error> {
+    serde_yaml::to_string(&to_pattern_file(proposals))
+}
+
+// The lines are grouped by their structure: the lines of a group have the same
+// whitespace and delimiter tokens at the same positions. The words which are the same
+// in every line of a group become literals, the others become parsers. The largest
+// groups come first.
+pub fn learn<'a, I>(lines: I, max_test_messages: usize) -> Vec<Proposal>
+    where I: IntoIterator<Item = &'a str>
+{
+    let mut groups: HashMap<String, Vec<Vec<Token<'a>>>> = HashMap::new();
+    let mut order = Vec::new();
+    for line in lines {
+        let tokens = tokenize(line);
+        let key = structure(&tokens);
+        if !groups.contains_key(&key) {
+            order.push(key.clone());
+        }
+        groups.entry(key).or_insert_with(Vec::new).push(tokens);
+    }
+
+    let mut proposals: Vec<Proposal> = order.iter()
+                                            .map(|key| propose(&groups[key], max_test_messages))
+                                            .collect();
+    // the sort is stable, so the groups with the same size keep the order of the corpus
+    proposals.sort_by(|a, b| b.support.cmp(&a.support));
+    proposals
+}
+
+fn structure(tokens: &[Token]) -> String {
+    let mut key = String::new();
+    for token in tokens {
+        match token.kind() {
+            TokenKind::Whitespace | TokenKind::Delimiter => key.push_str(token.text()),
+            _ => key.push('\u{0}'),
+        }
+    }
+    key
+}
+
+fn is_word(token: &Token) -> bool {
+    match token.kind() {
+        TokenKind::Whitespace | TokenKind::Delimiter => false,
+        _ => true,
+    }
+}
+
+fn propose(lines: &[Vec<Token>], max_test_messages: usize) -> Proposal {
+    let first = &lines[0];
+    let variable: Vec<bool> = (0..first.len())
+                                  .map(|i| {
+                                      is_word(&first[i]) &&
+                                      lines.iter().any(|line| line[i].text() != first[i].text())
+                                  })
+                                  .collect();
+    let names = name_parsers(first, &variable);
+
+    let mut pattern = String::new();
+    for (i, token) in first.iter().enumerate() {
+        if variable[i] {
+            let values: Vec<&str> = lines.iter().map(|line| line[i].text()).collect();
+            pattern.push_str(&propose_parser(&values, &names[&i]).to_string());
+        } else {
+            pattern.push_str(&::grammar::escape_literal(token.text()));
+        }
+    }
+
+    let mut test_messages: Vec<TestMessageProposal> = Vec::new();
+    for line in lines {
+        if test_messages.len() >= max_test_messages {
+            break;
+        }
+        let message: String = line.iter().map(|token| token.text()).collect();
+        if test_messages.iter().any(|test_message| test_message.message == message) {
+            continue;
+        }
+        let values = names.iter()
+                          .map(|(i, name)| (name.clone(), line[*i].text().to_owned()))
+                          .collect();
+        test_messages.push(TestMessageProposal {
+            message: message,
+            values: values,
+        });
+    }
+
+    Proposal {
+        uuid: Uuid::new_v4(),
+        pattern: pattern,
+        support: lines.len(),
+        test_messages: test_messages,
+    }
+}
+
+// A parser is named after the constant word before it, like `port` in `port 22` or
+// `uid` in `uid=0`, the others are numbered.
+fn name_parsers(tokens: &[Token], variable: &[bool]) -> BTreeMap<usize, String> {
+    let mut names = BTreeMap::new();
+    let mut used = BTreeSet::new();
+    for i in (0..tokens.len()).filter(|i| variable[*i]) {
+        let base = preceding_word(tokens, variable, i)
+                       .unwrap_or_else(|| format!("field{}", names.len() + 1));
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while used.contains(&name) {
+            name = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        used.insert(name.clone());
+        names.insert(i, name);
+    }
+    names
+}
+
+fn preceding_word(tokens: &[Token], variable: &[bool], index: usize) -> Option<String> {
+    let mut i = index;
+    let mut skipped_separator = false;
+    while i > 0 {
+        i -= 1;
+        let token = &tokens[i];
+        match token.kind() {
+            TokenKind::Whitespace => continue,
+            TokenKind::Delimiter if token.text() == "=" && !skipped_separator => {
+                skipped_separator = true;
+                continue;
+            }
+            TokenKind::Word if !variable[i] && is_identifier(token.text()) => {
+                return Some(token.text().to_lowercase());
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn is_identifier(word: &str) -> bool {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// INT if only digits were observed, SET if the observed characters are letters,
+// digits and a few punctuation characters, GREEDY otherwise. The set of a SET parser
+// is widened to the character classes of the observed characters.
+fn propose_parser(values: &[&str], name: &str) -> ParserSyntax {
+    let observed: BTreeSet<char> = values.iter().flat_map(|value| value.chars()).collect();
+    if observed.iter().all(|c| c.is_digit(10)) {
+        return ParserSyntax::new("INT", Some(name));
+    }
+    if observed.iter().all(|c| c.is_ascii_alphanumeric() || SET_PUNCTUATION.contains(*c)) {
+        let mut syntax = ParserSyntax::new("SET", Some(name));
+        syntax.positional(widen(&observed));
+        return syntax;
+    }
+    ParserSyntax::new("GREEDY", Some(name))
+}
+
+fn widen(observed: &BTreeSet<char>) -> String {
+    let lowercase_hex = observed.iter()
+                                .filter(|c| c.is_ascii_lowercase())
+                                .all(|c| *c <= 'f');
+    let uppercase_hex = observed.iter()
+                                .filter(|c| c.is_ascii_uppercase())
+                                .all(|c| *c <= 'F');
+    let mut set = String::new();
+    if observed.iter().any(|c| c.is_digit(10)) {
+        set.push_str("0123456789");
+    }
+    if observed.iter().any(|c| c.is_ascii_lowercase()) {
+        set.push_str(if lowercase_hex {
+            "abcdef"
+        } else {
+            "abcdefghijklmnopqrstuvwxyz"
+        });
+    }
+    if observed.iter().any(|c| c.is_ascii_uppercase()) {
+        set.push_str(if uppercase_hex {
+            "ABCDEF"
+        } else {
+            "ABCDEFGHIJKLMNOPQRSTUVWXYZ"
+        });
+    }
+    for c in observed.iter().filter(|c| !c.is_ascii_alphanumeric()) {
+        set.push(*c);
+    }
+    set
+}
+
+#[cfg(test)]
+mod test {
+    use super::{learn, to_json, to_yaml};
+    use matcher::pattern::file::PatternFile;
+    use matcher::trie::factory::TrieMatcherFactory;
+    use matcher::FromPatternSource;
+    use matcher::trie::SuffixTree;
+
+    use serde_json;
+    use serde_yaml;
+
+    const CORPUS: [&'static str; 5] = ["Accepted password for root from 10.0.0.1 port 22 ssh2",
+                                       "Accepted password for admin from 10.0.0.2 port 2222 \
+                                        ssh2",
+                                       "Accepted password for admin from 10.0.0.2 port 2222 \
+                                        ssh2",
+                                       "session 1f3a opened by user#1",
+                                       "session 2b opened by *admin*"];
+
+    #[test]
+    fn test_given_corpus_when_patterns_are_learnt_then_the_variable_words_become_parsers() {
+        let proposals = learn(CORPUS.iter().cloned(), 3);
+        assert_eq!(proposals.len(), 2);
+        assert_eq!(proposals[0].support(), 3);
+        assert_eq!(proposals[0].pattern(),
+                   "Accepted password for %{SET(\"abcdefghijklmnopqrstuvwxyz\"):for} from \
+                    %{SET(\"0123456789.\"):from} port %{INT:port} ssh2");
+        assert_eq!(proposals[0].test_messages().len(), 2);
+        assert_eq!(proposals[0].test_messages()[0].values().get("from"),
+                   Some(&"10.0.0.1".to_owned()));
+        assert_eq!(proposals[1].pattern(),
+                   "session %{SET(\"0123456789abcdef\"):session} opened by %{GREEDY:by}");
+    }
+
+    #[test]
+    fn test_given_learnt_patterns_when_they_are_written_then_they_can_be_loaded_with_their_test_messages
+        () {
+        let proposals = learn(CORPUS.iter().cloned(), 3);
+
+        let file = serde_json::from_str::<PatternFile>(&to_json(&proposals).unwrap()).unwrap();
+        SuffixTree::from_source::<TrieMatcherFactory>(&mut file.into_iter()).unwrap();
+
+        let file = serde_yaml::from_str::<PatternFile>(&to_yaml(&proposals).unwrap()).unwrap();
+        SuffixTree::from_source::<TrieMatcherFactory>(&mut file.into_iter()).unwrap();
+    }
+}
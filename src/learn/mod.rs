@@ -0,0 +1,5 @@
+pub mod cluster;
+pub mod generate;
+
+pub use self::cluster::{Cluster, Clusterer};
+pub use self::generate::{learn, Proposal};
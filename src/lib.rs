@@ -13,5 +13,6 @@ pub mod parsers;
 pub mod utils;
 pub mod matcher;
 pub mod grammar;
+pub mod learn;
 
 pub use matcher::Matcher;
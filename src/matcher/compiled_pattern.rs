@@ -1,7 +1,123 @@
-use parsers::Parser;
+use parsers::{Parser, CaseInsensitiveParser, SetParser};
+
+use std::error;
+use std::fmt;
 
 pub type CompiledPattern = Vec<TokenType>;
 
+pub const WHITESPACE: &'static str = " \t\r\n";
+
+// replaces the literals with parsers which match them case-insensitively. The
+// whitespace runs are kept as literals, so the pattern can be made whitespace tolerant
+// later.
+pub fn ignore_literal_case(pattern: CompiledPattern) -> CompiledPattern {
+    map_literals(pattern, |run, is_whitespace| {
+        if is_whitespace {
+            TokenType::Literal(run.to_owned())
+        } else {
+            TokenType::Parser(Box::new(CaseInsensitiveParser::new(run)))
+        }
+    })
+}
+
+// replaces the whitespace runs of the literals with parsers which match any non-empty
+// run of whitespace. The parsers are made whitespace tolerant too.
+pub fn tolerate_whitespace(pattern: CompiledPattern) -> CompiledPattern {
+    let mut pattern = map_literals(pattern, |run, is_whitespace| {
+        if is_whitespace {
+            TokenType::Parser(Box::new(SetParser::new(WHITESPACE)))
+        } else {
+            TokenType::Literal(run.to_owned())
+        }
+    });
+    for token in &mut pattern {
+        if let TokenType::Parser(ref mut parser) = *token {
+            parser.set_whitespace_tolerant(true);
+        }
+    }
+    pattern
+}
+
+// splits the literals into whitespace and non-whitespace runs and maps them
+fn map_literals<F>(pattern: CompiledPattern, f: F) -> CompiledPattern
+    where F: Fn(&str, bool) -> TokenType
+{
+    let mut result = Vec::new();
+    for token in pattern {
+        match token {
+            TokenType::Literal(literal) => {
+                let mut start = 0;
+                while start < literal.len() {
+                    let is_whitespace = is_whitespace(&literal[start..]);
+                    let len = literal[start..]
+                                  .find(|c| WHITESPACE.contains(c) != is_whitespace)
+                                  .unwrap_or(literal.len() - start);
+                    result.push(f(&literal[start..start + len], is_whitespace));
+                    start += len;
+                }
+            }
+            token => result.push(token),
+        }
+    }
+    result
+}
+
+fn is_whitespace(value: &str) -> bool {
+    value.chars().next().map_or(false, |c| WHITESPACE.contains(c))
+}
+
+// Writes the pattern in the syntax of the grammar. The literals are escaped and the
+// parsers are written in their canonical form.
+pub fn to_pattern_string(pattern: &CompiledPattern) -> Result<String, FormatError> {
+    let mut result = String::new();
+    for token in pattern {
+        match *token {
+            TokenType::Literal(ref literal) => {
+                result.push_str(&::grammar::escape_literal(literal))
+            }
+            TokenType::Parser(ref parser) => {
+                match parser.syntax() {
+                    Some(ref syntax) if syntax.is_writable() => {
+                        result.push_str(&syntax.to_string())
+                    }
+                    _ => return Err(FormatError::new(&format!("{:?}", parser))),
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+// a parser which can't be written, because its type doesn't provide its syntax or one
+// of its strings contains both a quotation mark and an apostrophe
+#[derive(Debug, PartialEq)]
+pub struct FormatError {
+    parser: String,
+}
+
+impl FormatError {
+    pub fn new(parser: &str) -> FormatError {
+        FormatError { parser: parser.to_owned() }
+    }
+
+    pub fn parser(&self) -> &str {
+        &self.parser
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter.write_fmt(format_args!("The parser cannot be written as a pattern: {}",
+                                         self.parser))
+    }
+}
+
+impl error::Error for FormatError {
+    fn description(&self) -> &str {
+        "The parser cannot be written as a pattern"
+    }
+}
+
 #[derive(Debug)]
 pub enum TokenType {
     Parser(Box<Parser>),
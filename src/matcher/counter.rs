@@ -0,0 +1,252 @@
+use uuid::Uuid;
+
+use matcher::{Matcher, MatcherFactory, Pattern};
+use matcher::result::MatchResult;
+use matcher::stats::MatcherStats;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Wraps a matcher and counts the matches of its patterns. The counters of the
+// patterns are created when the patterns are added, so parse() only increments atomic
+// counters without locking and snapshot() can be called at any time through a shared
+// reference. The counter doesn't know the patterns which were added to the matcher
+// before it was wrapped, their hits are counted together as untracked ones. The
+// matchers built by PatternLoader should be created with CountingMatcherFactory.
+pub struct CountingMatcher<M: Matcher> {
+    matcher: M,
+    hits: BTreeMap<Uuid, AtomicUsize>,
+    parsed: AtomicUsize,
+    unmatched: AtomicUsize,
+    untracked: AtomicUsize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HitCounts {
+    hits: BTreeMap<Uuid, usize>,
+    parsed: usize,
+    unmatched: usize,
+    untracked: usize,
+}
+
+impl HitCounts {
+    // every added pattern is in the map, the ones which never matched have 0 hits
+    pub fn hits(&self) -> &BTreeMap<Uuid, usize> {
+        &self.hits
+    }
+
+    pub fn hits_of(&self, uuid: &Uuid) -> Option<usize> {
+        self.hits.get(uuid).cloned()
+    }
+
+    pub fn parsed(&self) -> usize {
+        self.parsed
+    }
+
+    pub fn unmatched(&self) -> usize {
+        self.unmatched
+    }
+
+    // the matches of the patterns which weren't added through the counter, the parsed
+    // messages are the sum of the hits, the untracked and the unmatched ones
+    pub fn untracked(&self) -> usize {
+        self.untracked
+    }
+
+    pub fn never_matched(&self) -> Vec<&Uuid> {
+        self.hits.iter().filter(|&(_, hits)| *hits == 0).map(|(uuid, _)| uuid).collect()
+    }
+}
+
+impl<M: Matcher> CountingMatcher<M> {
+    pub fn new(matcher: M) -> CountingMatcher<M> {
+        CountingMatcher {
+            matcher: matcher,
+            hits: BTreeMap::new(),
+            parsed: AtomicUsize::new(0),
+            unmatched: AtomicUsize::new(0),
+            untracked: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn matcher(&self) -> &M {
+        &self.matcher
+    }
+
+    pub fn snapshot(&self) -> HitCounts {
+        HitCounts {
+            hits: self.hits
+                      .iter()
+                      .map(|(uuid, hits)| (*uuid, hits.load(Ordering::Relaxed)))
+                      .collect(),
+            parsed: self.parsed.load(Ordering::Relaxed),
+            unmatched: self.unmatched.load(Ordering::Relaxed),
+            untracked: self.untracked.load(Ordering::Relaxed),
+        }
+    }
+
+    // sets every counter to 0, the patterns are kept
+    pub fn reset(&self) {
+        for hits in self.hits.values() {
+            hits.store(0, Ordering::Relaxed);
+        }
+        self.parsed.store(0, Ordering::Relaxed);
+        self.unmatched.store(0, Ordering::Relaxed);
+        self.untracked.store(0, Ordering::Relaxed);
+    }
+
+    fn count(&self, result: Option<&MatchResult>) {
+        self.parsed.fetch_add(1, Ordering::Relaxed);
+        match result {
+            Some(result) => {
+                match self.hits.get(result.pattern().uuid()) {
+                    Some(hits) => hits.fetch_add(1, Ordering::Relaxed),
+                    None => self.untracked.fetch_add(1, Ordering::Relaxed),
+                };
+            }
+            None => {
+                self.unmatched.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl<M: Matcher + Clone> Clone for CountingMatcher<M> {
+    fn clone(&self) -> CountingMatcher<M> {
+        let counts = self.snapshot();
+        CountingMatcher {
+            matcher: self.matcher.clone(),
+            hits: counts.hits
+                        .iter()
+                        .map(|(uuid, hits)| (*uuid, AtomicUsize::new(*hits)))
+                        .collect(),
+            parsed: AtomicUsize::new(counts.parsed),
+            unmatched: AtomicUsize::new(counts.unmatched),
+            untracked: AtomicUsize::new(counts.untracked),
+        }
+    }
+}
+
+impl<M: Matcher> fmt::Debug for CountingMatcher<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CountingMatcher")
+         .field("matcher", &self.matcher)
+         .field("counts", &self.snapshot())
+         .finish()
+    }
+}
+
+// only parse() is counted, parse_all() is used for analysis
+impl<M: Matcher + Clone + 'static> Matcher for CountingMatcher<M> {
+    fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
+        let result = self.matcher.parse(text);
+        self.count(result.as_ref());
+        result
+    }
+    fn parse_all<'a, 'b>(&'a self, text: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        self.matcher.parse_all(text)
+    }
+    fn add_pattern(&mut self, pattern: Pattern) {
+        self.hits.entry(*pattern.uuid()).or_insert_with(|| AtomicUsize::new(0));
+        self.matcher.add_pattern(pattern);
+    }
+    fn stats(&self) -> MatcherStats {
+        self.matcher.stats()
+    }
+    fn boxed_clone(&self) -> Box<Matcher> {
+        Box::new(self.clone())
+    }
+}
+
+// creates counting matchers around the matchers of an other factory, so the patterns
+// loaded by PatternLoader are added through the counter. The test messages are
+// counted while the patterns are loaded, call reset() before counting the logs.
+pub struct CountingMatcherFactory<F: MatcherFactory> {
+    _factory: PhantomData<F>,
+}
+
+impl<F> MatcherFactory for CountingMatcherFactory<F>
+    where F: MatcherFactory,
+          F::Matcher: Clone + 'static
+{
+    type Matcher = CountingMatcher<F::Matcher>;
+
+    fn new_matcher() -> Self::Matcher {
+        CountingMatcher::new(F::new_matcher())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CountingMatcher;
+    use matcher::{Matcher, Pattern};
+    use matcher::trie::SuffixTree;
+    use uuid::Uuid;
+
+    fn create_pattern(uuid: &Uuid, input: &str) -> Pattern {
+        let mut pattern = Pattern::with_uuid(*uuid);
+        pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+        pattern
+    }
+
+    #[test]
+    fn test_given_counting_matcher_when_messages_are_parsed_then_the_hits_are_counted_per_pattern() {
+        let mut matcher = CountingMatcher::new(SuffixTree::new());
+        let uuids = [Uuid::new_v4(), Uuid::new_v4()];
+        for (uuid, input) in uuids.iter().zip(&["user %{INT:uid} logged in", "user %{INT:uid} logged out"]) {
+            matcher.add_pattern(create_pattern(uuid, input));
+        }
+
+        for _ in 0..4 {
+            matcher.parse("user 0 logged in");
+            matcher.parse("user 0 logged on");
+        }
+        assert_eq!(matcher.snapshot().parsed(), 8);
+        matcher.parse("user 0 logged in");
+
+        let counts = matcher.snapshot();
+        assert_eq!(counts.parsed(), 9);
+        assert_eq!(counts.unmatched(), 4);
+        assert_eq!(counts.hits_of(&uuids[0]), Some(5));
+        assert_eq!(counts.hits_of(&uuids[1]), Some(0));
+        assert_eq!(counts.never_matched(), vec![&uuids[1]]);
+    }
+
+    #[test]
+    fn test_given_counting_matcher_when_it_wraps_a_boxed_matcher_then_the_hits_are_counted() {
+        let boxed: Box<Matcher> = Box::new(SuffixTree::new());
+        let mut matcher = CountingMatcher::new(boxed);
+        let uuid = Uuid::new_v4();
+        matcher.add_pattern(create_pattern(&uuid, "user %{INT:uid} logged in"));
+
+        matcher.parse("user 0 logged in");
+        matcher.parse("user 0 logged on");
+        assert_eq!(matcher.clone().snapshot().hits_of(&uuid), Some(1));
+        assert_eq!(matcher.snapshot().unmatched(), 1);
+    }
+
+    #[test]
+    fn test_given_counting_matcher_when_it_wraps_a_matcher_with_patterns_then_their_hits_are_untracked
+        () {
+        let mut trie = SuffixTree::new();
+        let added_before = Uuid::new_v4();
+        trie.add_pattern(create_pattern(&added_before, "user %{INT:uid} logged in"));
+        let mut matcher = CountingMatcher::new(trie);
+        let added_after = Uuid::new_v4();
+        matcher.add_pattern(create_pattern(&added_after, "user %{INT:uid} logged out"));
+
+        matcher.parse("user 0 logged in");
+        matcher.parse("user 0 logged in");
+        matcher.parse("user 0 logged out");
+        matcher.parse("user 0 logged on");
+
+        let counts = matcher.snapshot();
+        assert_eq!(counts.parsed(), 4);
+        assert_eq!(counts.untracked(), 2);
+        assert_eq!(counts.hits_of(&added_before), None);
+        assert_eq!(counts.hits_of(&added_after), Some(1));
+        assert_eq!(counts.unmatched(), 1);
+    }
+}
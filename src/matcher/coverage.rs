@@ -0,0 +1,150 @@
+use uuid::Uuid;
+
+use matcher::dump::{DumpNode, EdgeKind};
+
+use std::collections::BTreeSet;
+
+// A test message exercises the literal and parser nodes on the path of the pattern it
+// matched, so a node is covered if a matched pattern ends in it or under it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CoverageReport {
+    literal_nodes: usize,
+    covered_literal_nodes: usize,
+    parser_nodes: usize,
+    covered_parser_nodes: usize,
+    uncovered_branches: Vec<String>,
+    uncovered_patterns: Vec<Uuid>,
+    untested_patterns: Vec<Uuid>,
+}
+
+impl CoverageReport {
+    // matched: the patterns matched by the test messages, tested: the patterns which
+    // have test messages
+    pub fn new(root: &DumpNode, matched: &BTreeSet<Uuid>, tested: &BTreeSet<Uuid>) -> CoverageReport {
+        let mut report = CoverageReport::default();
+        for child in root.children() {
+            report.visit(child, "", matched, tested);
+        }
+        report
+    }
+
+    // returns whether the node is covered
+    fn visit(&mut self,
+             node: &DumpNode,
+             path: &str,
+             matched: &BTreeSet<Uuid>,
+             tested: &BTreeSet<Uuid>)
+             -> bool {
+        let path = format!("{}{}", path, node.label());
+        let mut covered = false;
+        if let Some(uuid) = node.pattern() {
+            covered = matched.contains(uuid);
+            if !tested.contains(uuid) {
+                self.untested_patterns.push(*uuid);
+            } else if !covered {
+                self.uncovered_patterns.push(*uuid);
+            }
+        }
+        let uncovered_branches = self.uncovered_branches.len();
+        for child in node.children() {
+            covered |= self.visit(child, &path, matched, tested);
+        }
+        // only the root of an uncovered subtree is reported
+        if !covered {
+            self.uncovered_branches.truncate(uncovered_branches);
+            self.uncovered_branches.push(path);
+        }
+
+        match node.kind() {
+            EdgeKind::Literal => {
+                self.literal_nodes += 1;
+                self.covered_literal_nodes += covered as usize;
+            }
+            EdgeKind::Parser => {
+                self.parser_nodes += 1;
+                self.covered_parser_nodes += covered as usize;
+            }
+            EdgeKind::Root => {}
+        }
+        covered
+    }
+
+    pub fn literal_nodes(&self) -> usize {
+        self.literal_nodes
+    }
+
+    pub fn covered_literal_nodes(&self) -> usize {
+        self.covered_literal_nodes
+    }
+
+    pub fn parser_nodes(&self) -> usize {
+        self.parser_nodes
+    }
+
+    pub fn covered_parser_nodes(&self) -> usize {
+        self.covered_parser_nodes
+    }
+
+    // the covered nodes per all nodes, it's 1.0 for an empty matcher
+    pub fn ratio(&self) -> f64 {
+        let nodes = self.literal_nodes + self.parser_nodes;
+        if nodes == 0 {
+            1.0
+        } else {
+            (self.covered_literal_nodes + self.covered_parser_nodes) as f64 / nodes as f64
+        }
+    }
+
+    // the beginnings of the messages which would reach the uncovered subtrees, the
+    // parsers are written as they are written in patterns
+    pub fn uncovered_branches(&self) -> &[String] {
+        &self.uncovered_branches
+    }
+
+    // the patterns which have test messages but none of them matched the pattern
+    pub fn uncovered_patterns(&self) -> &[Uuid] {
+        &self.uncovered_patterns
+    }
+
+    // the patterns without test messages, even if another pattern's test message
+    // matched them
+    pub fn untested_patterns(&self) -> &[Uuid] {
+        &self.untested_patterns
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CoverageReport;
+    use matcher::dump::DumpNode;
+    use parsers::IntParser;
+    use uuid::Uuid;
+
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn test_given_dump_tree_when_the_coverage_is_calculated_then_the_nodes_on_the_paths_of_the_matched_patterns_are_covered
+        () {
+        let (matched, uncovered, untested) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut root = DumpNode::root();
+        let mut literal = DumpNode::literal("pid=", None);
+        literal.push_child(DumpNode::parser(&IntParser::with_name("pid"), Some(matched)));
+        let mut uid = DumpNode::literal("uid=", None);
+        uid.push_child(DumpNode::parser(&IntParser::with_name("uid"), Some(uncovered)));
+        root.push_child(literal);
+        root.push_child(uid);
+        root.push_child(DumpNode::literal("exit", Some(untested)));
+
+        let coverage = CoverageReport::new(&root,
+                                           &[matched].iter().cloned().collect(),
+                                           &[matched, uncovered].iter().cloned().collect::<BTreeSet<_>>());
+        assert_eq!(coverage.literal_nodes(), 3);
+        assert_eq!(coverage.covered_literal_nodes(), 1);
+        assert_eq!(coverage.parser_nodes(), 2);
+        assert_eq!(coverage.covered_parser_nodes(), 1);
+        assert_eq!(coverage.ratio(), 0.4);
+        assert_eq!(coverage.uncovered_branches(), &["uid=".to_owned(), "exit".to_owned()][..]);
+        assert_eq!(coverage.uncovered_patterns(), &[uncovered][..]);
+        assert_eq!(coverage.untested_patterns(), &[untested][..]);
+    }
+}
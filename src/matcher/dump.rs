@@ -0,0 +1,217 @@
+use uuid::Uuid;
+
+use parsers::Parser;
+
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EdgeKind {
+    Root,
+    Literal,
+    Parser,
+}
+
+// A matcher independent description of a node and the edge which leads to it. The
+// label of a literal edge is the literal, the label of a parser edge is the parser as
+// it's written in a pattern.
+#[derive(Clone, Debug)]
+pub struct DumpNode {
+    kind: EdgeKind,
+    label: String,
+    pattern: Option<Uuid>,
+    children: Vec<DumpNode>,
+}
+
+impl DumpNode {
+    pub fn root() -> DumpNode {
+        DumpNode::new(EdgeKind::Root, String::new(), None)
+    }
+
+    pub fn literal(literal: &str, pattern: Option<Uuid>) -> DumpNode {
+        DumpNode::new(EdgeKind::Literal, literal.to_owned(), pattern)
+    }
+
+    pub fn parser(parser: &Parser, pattern: Option<Uuid>) -> DumpNode {
+        let label = match parser.syntax() {
+            Some(syntax) => syntax.to_string(),
+            None => format!("{:?}", parser),
+        };
+        DumpNode::new(EdgeKind::Parser, label, pattern)
+    }
+
+    fn new(kind: EdgeKind, label: String, pattern: Option<Uuid>) -> DumpNode {
+        DumpNode {
+            kind: kind,
+            label: label,
+            pattern: pattern,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn kind(&self) -> EdgeKind {
+        self.kind
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn pattern(&self) -> Option<&Uuid> {
+        self.pattern.as_ref()
+    }
+
+    pub fn children(&self) -> &[DumpNode] {
+        &self.children
+    }
+
+    pub fn push_child(&mut self, child: DumpNode) {
+        self.children.push(child);
+    }
+
+    // the number of literal nodes, parser nodes and patterns in the subtree
+    pub fn counts(&self) -> (usize, usize, usize) {
+        let mut counts = match self.kind {
+            EdgeKind::Root => (0, 0, 0),
+            EdgeKind::Literal => (1, 0, 0),
+            EdgeKind::Parser => (0, 1, 0),
+        };
+        if self.pattern.is_some() {
+            counts.2 += 1;
+        }
+        for child in &self.children {
+            let (literals, parsers, patterns) = child.counts();
+            counts.0 += literals;
+            counts.1 += parsers;
+            counts.2 += patterns;
+        }
+        counts
+    }
+
+    fn summary(&self) -> String {
+        let (literals, parsers, patterns) = self.counts();
+        format!("literal nodes: {}, parser nodes: {}, patterns: {}",
+                literals,
+                parsers,
+                patterns)
+    }
+
+    fn edge_label(&self) -> String {
+        match self.kind {
+            EdgeKind::Root => "root".to_owned(),
+            EdgeKind::Literal => format!("{:?}", self.label),
+            EdgeKind::Parser => self.label.clone(),
+        }
+    }
+}
+
+pub trait Dump {
+    fn dump(&self) -> DumpNode;
+}
+
+// Every node is written in its own line, indented by its depth. The literals are
+// quoted, so the whitespace in them is visible.
+pub fn write_text<W: Write>(root: &DumpNode, out: &mut W) -> io::Result<()> {
+    try!(writeln!(out, "{}", root.summary()));
+    for child in root.children() {
+        try!(write_text_node(child, 0, out));
+    }
+    Ok(())
+}
+
+fn write_text_node<W: Write>(node: &DumpNode, depth: usize, out: &mut W) -> io::Result<()> {
+    let indent: String = (0..depth).map(|_| "  ").collect();
+    match node.pattern() {
+        Some(uuid) => {
+            try!(writeln!(out, "{}{} => {}", indent, node.edge_label(), uuid.hyphenated()))
+        }
+        None => try!(writeln!(out, "{}{}", indent, node.edge_label())),
+    }
+    for child in node.children() {
+        try!(write_text_node(child, depth + 1, out));
+    }
+    Ok(())
+}
+
+// The edges are labeled with the literals and the parsers (the parser edges are
+// dashed), the nodes where a pattern ends are labeled with its UUID.
+pub fn write_dot<W: Write>(root: &DumpNode, out: &mut W) -> io::Result<()> {
+    try!(writeln!(out, "digraph matcher {{"));
+    try!(writeln!(out, "  label=\"{}\";", escape_dot(&root.summary())));
+    try!(writeln!(out, "  n0 [shape=circle, label=\"root\"];"));
+    let mut next_id = 1;
+    try!(write_dot_children(root, 0, &mut next_id, out));
+    writeln!(out, "}}")
+}
+
+fn write_dot_children<W: Write>(node: &DumpNode,
+                                id: usize,
+                                next_id: &mut usize,
+                                out: &mut W)
+                                -> io::Result<()> {
+    for child in node.children() {
+        let child_id = *next_id;
+        *next_id += 1;
+        match child.pattern() {
+            Some(uuid) => {
+                try!(writeln!(out,
+                              "  n{} [shape=box, label=\"{}\"];",
+                              child_id,
+                              uuid.hyphenated()))
+            }
+            None => try!(writeln!(out, "  n{} [shape=point];", child_id)),
+        }
+        let style = if child.kind() == EdgeKind::Parser {
+            ", style=dashed"
+        } else {
+            ""
+        };
+        try!(writeln!(out,
+                      "  n{} -> n{} [label=\"{}\"{}];",
+                      id,
+                      child_id,
+                      escape_dot(&child.edge_label()),
+                      style));
+        try!(write_dot_children(child, child_id, next_id, out));
+    }
+    Ok(())
+}
+
+fn escape_dot(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DumpNode, write_text, write_dot};
+    use parsers::IntParser;
+    use uuid::Uuid;
+
+    fn create_tree() -> DumpNode {
+        let uuid = Uuid::parse_str("9a49c47d-29e9-4072-be84-3b76c6814743").unwrap();
+        let mut root = DumpNode::root();
+        let mut literal = DumpNode::literal("pid=", None);
+        literal.push_child(DumpNode::parser(&IntParser::with_name("pid"), Some(uuid)));
+        root.push_child(literal);
+        root
+    }
+
+    #[test]
+    fn test_given_dump_tree_when_it_is_written_as_text_then_the_nodes_are_indented_by_their_depth
+        () {
+        let mut out = Vec::new();
+        write_text(&create_tree(), &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(),
+                   "literal nodes: 1, parser nodes: 1, patterns: 1\n\"pid=\"\n  %{INT:pid} => \
+                    9a49c47d-29e9-4072-be84-3b76c6814743\n");
+    }
+
+    #[test]
+    fn test_given_dump_tree_when_it_is_written_as_dot_then_the_literals_are_escaped() {
+        let mut out = Vec::new();
+        write_dot(&create_tree(), &mut out).unwrap();
+        let dot = String::from_utf8(out).unwrap();
+        assert!(dot.contains("  n0 -> n1 [label=\"\\\"pid=\\\"\"];\n"));
+        assert!(dot.contains("  n1 -> n2 [label=\"%{INT:pid}\", style=dashed];\n"));
+        assert!(dot.contains("  n2 [shape=box, label=\"9a49c47d-29e9-4072-be84-3b76c6814743\"];\n"));
+    }
+}
@@ -7,6 +7,10 @@ pub mod pattern_loader;
 pub mod suite;
 pub mod compiled_pattern;
 pub mod suffix_array;
+pub mod dump;
+pub mod stats;
+pub mod counter;
+pub mod coverage;
 
 pub use self::pattern::Pattern;
 pub use self::pattern_loader::PatternLoader;
@@ -15,10 +19,90 @@ pub use self::suite::MatcherSuite;
 pub use self::pattern_source::{FromPatternSource, BuildError};
 
 use matcher::result::MatchResult;
+use matcher::stats::MatcherStats;
 use std::fmt;
 
 pub trait Matcher: fmt::Debug {
     fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>>;
+    // returns every complete match of the text, not just the one returned by parse().
+    // The matchers which can't enumerate their matches return only that one.
+    fn parse_all<'a, 'b>(&'a self, text: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        self.parse(text).into_iter().collect()
+    }
     fn add_pattern(&mut self, pattern: Pattern);
+    // the matchers which can't walk their structure return empty statistics
+    fn stats(&self) -> MatcherStats {
+        MatcherStats::new()
+    }
     fn boxed_clone(&self) -> Box<Matcher>;
 }
+
+// a matcher which is chosen at runtime can be wrapped like the concrete ones
+impl Matcher for Box<Matcher> {
+    fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
+        (**self).parse(text)
+    }
+    fn parse_all<'a, 'b>(&'a self, text: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        (**self).parse_all(text)
+    }
+    fn add_pattern(&mut self, pattern: Pattern) {
+        (**self).add_pattern(pattern)
+    }
+    fn stats(&self) -> MatcherStats {
+        (**self).stats()
+    }
+    fn boxed_clone(&self) -> Box<Matcher> {
+        (**self).boxed_clone()
+    }
+}
+
+impl Clone for Box<Matcher> {
+    fn clone(&self) -> Box<Matcher> {
+        self.boxed_clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use matcher::{Matcher, Pattern};
+    use matcher::result::MatchResult;
+    use matcher::stats::MatcherStats;
+
+    // matches only the UUID of its pattern
+    #[derive(Clone, Debug)]
+    struct UuidMatcher {
+        pattern: Pattern,
+    }
+
+    impl Matcher for UuidMatcher {
+        fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
+            if text == self.pattern.uuid().hyphenated().to_string() {
+                Some(MatchResult::new(&self.pattern))
+            } else {
+                None
+            }
+        }
+        fn add_pattern(&mut self, pattern: Pattern) {
+            self.pattern = pattern;
+        }
+        fn boxed_clone(&self) -> Box<Matcher> {
+            Box::new(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_given_matcher_without_parse_all_when_it_is_called_then_the_result_of_parse_is_returned() {
+        let matcher = UuidMatcher { pattern: Pattern::with_random_uuid() };
+        let uuid = matcher.pattern.uuid().hyphenated().to_string();
+        let results = matcher.parse_all(&uuid);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].pattern().uuid(), matcher.pattern.uuid());
+        assert_eq!(matcher.parse_all("foo").is_empty(), true);
+    }
+
+    #[test]
+    fn test_given_matcher_without_stats_when_it_is_called_then_empty_stats_are_returned() {
+        let matcher = UuidMatcher { pattern: Pattern::with_random_uuid() };
+        assert_eq!(matcher.stats(), MatcherStats::new());
+    }
+}
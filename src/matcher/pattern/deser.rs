@@ -21,6 +21,9 @@ enum Field {
     VALUES,
     TAGS,
     TESTMESSAGES,
+    CASEINSENSITIVE,
+    WHITESPACETOLERANT,
+    PRIORITY,
 }
 
 impl serde::Deserialize for Field {
@@ -42,6 +45,9 @@ impl serde::Deserialize for Field {
                     "values" => Ok(Field::VALUES),
                     "tags" => Ok(Field::TAGS),
                     "test_messages" => Ok(Field::TESTMESSAGES),
+                    "case_insensitive" => Ok(Field::CASEINSENSITIVE),
+                    "whitespace_tolerant" => Ok(Field::WHITESPACETOLERANT),
+                    "priority" => Ok(Field::PRIORITY),
                     _ => Err(serde::de::Error::custom(format!("Unexpected field: {}", value))),
                 }
             }
@@ -66,6 +72,9 @@ impl serde::de::Visitor for PatternVisitor {
         let mut values: Option<BTreeMap<String, String>> = None;
         let mut tags: Option<Vec<String>> = None;
         let mut test_messages: Option<Vec<TestMessage>> = None;
+        let mut case_insensitive = false;
+        let mut whitespace_tolerant: Option<bool> = None;
+        let mut priority = 0;
 
         while let Some(field) = try!(visitor.visit_key()) {
             match field {
@@ -75,6 +84,11 @@ impl serde::de::Visitor for PatternVisitor {
                 Field::VALUES => values = Some(try!(visitor.visit_value())),
                 Field::TAGS => tags = Some(try!(visitor.visit_value())),
                 Field::TESTMESSAGES => test_messages = Some(try!(visitor.visit_value())),
+                Field::CASEINSENSITIVE => case_insensitive = try!(visitor.visit_value()),
+                Field::WHITESPACETOLERANT => {
+                    whitespace_tolerant = Some(try!(visitor.visit_value()))
+                }
+                Field::PRIORITY => priority = try!(visitor.visit_value()),
             }
         }
 
@@ -103,6 +117,12 @@ impl serde::de::Visitor for PatternVisitor {
 
         try!(visitor.end());
 
-        Ok(Pattern::new(name, uuid, pattern, test_messages, values, tags))
+        let mut pattern = Pattern::new(name, uuid, pattern, test_messages, values, tags);
+        pattern.set_priority(priority);
+        pattern.set_case_insensitive(case_insensitive);
+        if let Some(whitespace_tolerant) = whitespace_tolerant {
+            pattern.set_whitespace_tolerant(whitespace_tolerant);
+        }
+        Ok(pattern)
     }
 }
@@ -13,6 +13,8 @@ impl serde::de::Deserialize for PatternFile {
 
 enum Field {
     PATTERNS,
+    WHITESPACETOLERANT,
+    STRICTTESTMESSAGES,
 }
 
 impl serde::Deserialize for Field {
@@ -29,6 +31,8 @@ impl serde::Deserialize for Field {
             {
                 match value {
                     "patterns" => Ok(Field::PATTERNS),
+                    "whitespace_tolerant" => Ok(Field::WHITESPACETOLERANT),
+                    "strict_test_messages" => Ok(Field::STRICTTESTMESSAGES),
                     _ => Err(serde::de::Error::custom(format!("Unexpected field: {}", value))),
                 }
             }
@@ -47,10 +51,14 @@ impl serde::de::Visitor for FileVisitor {
         where V: serde::de::MapVisitor
     {
         let mut patterns: Option<Vec<Pattern>> = None;
+        let mut whitespace_tolerant = false;
+        let mut strict_test_messages = false;
 
         while let Some (field) = try!(visitor.visit_key()) {
             match field {
-                Field::PATTERNS => patterns = Some(try!(visitor.visit_value()))
+                Field::PATTERNS => patterns = Some(try!(visitor.visit_value())),
+                Field::WHITESPACETOLERANT => whitespace_tolerant = try!(visitor.visit_value()),
+                Field::STRICTTESTMESSAGES => strict_test_messages = try!(visitor.visit_value()),
             }
         }
 
@@ -61,6 +69,10 @@ impl serde::de::Visitor for FileVisitor {
 
         try!(visitor.end());
 
-        Ok(PatternFile { patterns: patterns_final })
+        Ok(PatternFile {
+            patterns: patterns_final,
+            whitespace_tolerant: whitespace_tolerant,
+            strict_test_messages: strict_test_messages,
+        })
     }
 }
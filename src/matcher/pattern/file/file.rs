@@ -2,10 +2,22 @@ use matcher::pattern::Pattern;
 
 pub struct PatternFile {
     pub patterns: Vec<Pattern>,
+    // the defaults of the patterns, they are applied to the patterns when the file is
+    // iterated, so they are written back when the file is serialized
+    pub whitespace_tolerant: bool,
+    pub strict_test_messages: bool,
 }
 
 impl PatternFile {
     pub fn patterns(&self) -> &Vec<Pattern> {
         &self.patterns
     }
+
+    pub fn whitespace_tolerant(&self) -> bool {
+        self.whitespace_tolerant
+    }
+
+    pub fn strict_test_messages(&self) -> bool {
+        self.strict_test_messages
+    }
 }
@@ -0,0 +1,219 @@
+use serde_json::{self, Value};
+use serde_yaml;
+use uuid::Uuid;
+
+use super::PatternFile;
+use matcher::BuildError;
+
+use std::cmp::Ordering;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FileFormat {
+    Json,
+    Yaml,
+}
+
+impl FileFormat {
+    // the extensions are compared case-insensitively
+    pub fn from_extension(extension: &str) -> Option<FileFormat> {
+        match &extension.to_lowercase()[..] {
+            "json" => Some(FileFormat::Json),
+            "yaml" | "yml" => Some(FileFormat::Yaml),
+            _ => None,
+        }
+    }
+
+    // A JSON pattern file is an object, so it starts with a brace, a YAML pattern file
+    // is a mapping. Anything else (like a plain text or a broken YAML document) can't be
+    // told apart from garbage, so its format is unknown.
+    pub fn detect(content: &str) -> Option<FileFormat> {
+        match content.trim_left().chars().next() {
+            Some('{') => Some(FileFormat::Json),
+            None => None,
+            Some(_) => {
+                match serde_yaml::from_str::<Value>(content) {
+                    Ok(Value::Object(_)) => Some(FileFormat::Yaml),
+                    Ok(_) | Err(_) => None,
+                }
+            }
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match *self {
+            FileFormat::Json => "json",
+            FileFormat::Yaml => "yaml",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortOrder {
+    // the patterns without a name come last, the patterns with the same name are
+    // ordered by their UUIDs
+    Name,
+    Uuid,
+}
+
+// Reads a pattern file like the deserializer does, but the patterns without a UUID get
+// a random one instead of failing.
+pub fn read_filling_uuids(content: &str, format: FileFormat) -> Result<PatternFile, BuildError> {
+    let mut value: Value = match format {
+        FileFormat::Json => try!(serde_json::from_str(content)),
+        FileFormat::Yaml => try!(serde_yaml::from_str(content)),
+    };
+    if let Some(&mut Value::Array(ref mut patterns)) = value.as_object_mut()
+                                                             .and_then(|file| {
+                                                                 file.get_mut("patterns")
+                                                             }) {
+        for pattern in patterns {
+            if let Value::Object(ref mut pattern) = *pattern {
+                if !pattern.contains_key("uuid") {
+                    let uuid = Uuid::new_v4().hyphenated().to_string();
+                    pattern.insert("uuid".to_owned(), Value::String(uuid));
+                }
+            }
+        }
+    }
+    Ok(try!(serde_json::from_value(value)))
+}
+
+pub fn sort_patterns(file: &mut PatternFile, order: SortOrder) {
+    file.patterns.sort_by(|a, b| {
+        let by_name = match order {
+            SortOrder::Name => {
+                match (a.name(), b.name()) {
+                    (Some(a), Some(b)) => a.cmp(b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            }
+            SortOrder::Uuid => Ordering::Equal,
+        };
+        match by_name {
+            Ordering::Equal => a.uuid().cmp(b.uuid()),
+            ordering => ordering,
+        }
+    });
+}
+
+pub fn write(file: &PatternFile, format: FileFormat) -> Result<String, BuildError> {
+    let mut content = match format {
+        FileFormat::Json => try!(serde_json::to_string_pretty(file)),
+        FileFormat::Yaml => try!(serde_yaml::to_string(file)),
+    };
+    content.push('\n');
+    Ok(content)
+}
+
+// Rewrites a pattern file in its canonical form: the fields and the optional parser
+// parameters are written in a fixed order, the strings are quoted the same way and the
+// missing UUIDs are generated. The literals are written as they are authored, even in
+// the case-insensitive and whitespace tolerant patterns. The formatted file doesn't
+// change when it's formatted again.
+pub fn format(content: &str,
+              input: FileFormat,
+              output: FileFormat,
+              order: Option<SortOrder>)
+              -> Result<String, BuildError> {
+    let mut file = try!(read_filling_uuids(content, input));
+    if let Some(order) = order {
+        sort_patterns(&mut file, order);
+    }
+    write(&file, output)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{format, FileFormat, SortOrder};
+    use matcher::{Matcher, PatternLoader};
+    use matcher::trie::factory::TrieMatcherFactory;
+
+    const FILE: &'static str = r#"
+{
+  "patterns": [
+    {
+      "pattern": "user %{INT(min_len=1,max_len=3):uid} logged in",
+      "name": "LOGIN",
+      "test_messages": [{"message": "user 1 logged in", "values": {"uid": "1"}}]
+    },
+    {
+      "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+      "pattern": "%{SET('ab'):x} \\%\\{",
+      "name": "ESCAPED"
+    }
+  ]
+}
+"#;
+
+    #[test]
+    fn test_given_pattern_file_content_when_its_format_is_detected_then_json_objects_are_recognized
+        () {
+        assert_eq!(FileFormat::detect(FILE), Some(FileFormat::Json));
+        assert_eq!(FileFormat::detect("patterns:\n  - pattern: \"{\"\n"),
+                   Some(FileFormat::Yaml));
+        assert_eq!(FileFormat::detect("user 1 logged in\n"), None);
+        assert_eq!(FileFormat::detect(""), None);
+    }
+
+    #[test]
+    fn test_given_file_extension_when_its_case_differs_then_the_format_is_still_recognized() {
+        assert_eq!(FileFormat::from_extension("JSON"), Some(FileFormat::Json));
+        assert_eq!(FileFormat::from_extension("Json"), Some(FileFormat::Json));
+        assert_eq!(FileFormat::from_extension("YML"), Some(FileFormat::Yaml));
+        assert_eq!(FileFormat::from_extension("yaml"), Some(FileFormat::Yaml));
+        assert_eq!(FileFormat::from_extension("txt"), None);
+    }
+
+    #[test]
+    fn test_given_pattern_file_when_it_is_formatted_then_it_is_written_in_canonical_form() {
+        let yaml = format(FILE, FileFormat::Json, FileFormat::Yaml, Some(SortOrder::Name))
+                       .unwrap();
+        let escaped = yaml.find("ESCAPED").unwrap();
+        let login = yaml.find("LOGIN").unwrap();
+        assert!(escaped < login);
+        assert!(yaml.contains(r#"%{SET(\"ab\"):x} \\%\\{"#), "{}", yaml);
+        assert!(yaml.contains("user %{INT(max_len=3,min_len=1):uid} logged in"));
+        assert_eq!(yaml.matches("uuid").count(), 2);
+
+        assert_eq!(format(&yaml, FileFormat::Yaml, FileFormat::Yaml, Some(SortOrder::Name))
+                       .unwrap(),
+                   yaml);
+        let json = format(&yaml, FileFormat::Yaml, FileFormat::Json, None).unwrap();
+        assert_eq!(format(&json, FileFormat::Json, FileFormat::Yaml, None).unwrap(), yaml);
+    }
+
+    #[test]
+    fn test_given_case_insensitive_and_whitespace_tolerant_patterns_when_they_are_formatted_then_they_are_not_changed
+        () {
+        let file = r#"
+{
+  "whitespace_tolerant": true,
+  "patterns": [
+    {
+      "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+      "pattern": "Disk  Error:	%{ICASE(\"On\")} %{GREEDY:device} At  %{INT:sector}",
+      "case_insensitive": true,
+      "test_messages": [{"message": "disk error: ON sda1  At 42"}]
+    }
+  ]
+}
+"#;
+        let json = format(file, FileFormat::Json, FileFormat::Json, None).unwrap();
+        assert!(json.contains(r#""whitespace_tolerant": true"#), "{}", json);
+        assert!(json.contains(r#"Disk  Error:\t%{ICASE(\"On\")} %{GREEDY:device} At  %{INT:sector}"#),
+                "{}",
+                json);
+        assert_eq!(format(&json, FileFormat::Json, FileFormat::Json, None).unwrap(), json);
+
+        let yaml = format(&json, FileFormat::Json, FileFormat::Yaml, None).unwrap();
+        assert_eq!(format(&yaml, FileFormat::Yaml, FileFormat::Json, None).unwrap(), json);
+
+        let matcher = PatternLoader::from_str::<TrieMatcherFactory>(&json, FileFormat::Json)
+                          .unwrap();
+        let result = matcher.parse("DISK ERROR: on sda1 At\t42").unwrap();
+        assert_eq!(result.values().get("device").map(|value| &value[..]), Some("sda1"));
+    }
+}
+
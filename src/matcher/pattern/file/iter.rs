@@ -9,12 +9,18 @@ impl iter::IntoIterator for PatternFile {
     type IntoIter = IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
-        IntoIter { patterns: self.patterns }
+        IntoIter {
+            patterns: self.patterns,
+            whitespace_tolerant: self.whitespace_tolerant,
+            strict_test_messages: self.strict_test_messages,
+        }
     }
 }
 
 pub struct IntoIter {
     patterns: Vec<Pattern>,
+    whitespace_tolerant: bool,
+    strict_test_messages: bool,
 }
 
 impl Iterator for IntoIter {
@@ -22,7 +28,16 @@ impl Iterator for IntoIter {
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.patterns.pop() {
-            Some(pattern) => Some(Ok(pattern)),
+            Some(mut pattern) => {
+                // the patterns can override the defaults of the file, which are false
+                if self.whitespace_tolerant && pattern.whitespace_tolerant().is_none() {
+                    pattern.set_whitespace_tolerant(true);
+                }
+                if self.strict_test_messages {
+                    pattern.set_strict_test_messages(true);
+                }
+                Some(Ok(pattern))
+            }
             None => None,
         }
     }
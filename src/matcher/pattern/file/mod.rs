@@ -1,5 +1,8 @@
 pub use self::file::PatternFile;
+pub use self::format::{FileFormat, SortOrder};
 
 mod deser;
+mod ser;
 mod file;
 mod iter;
+pub mod format;
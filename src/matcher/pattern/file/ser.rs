@@ -0,0 +1,56 @@
+use super::PatternFile;
+
+use serde;
+
+impl serde::Serialize for PatternFile {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        serializer.serialize_struct("File",
+                                    FileMapVisitor {
+                                        value: self,
+                                        state: 0,
+                                    })
+    }
+}
+
+struct FileMapVisitor<'a> {
+    value: &'a PatternFile,
+    state: u8,
+}
+
+impl<'a> serde::ser::MapVisitor for FileMapVisitor<'a> {
+    fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+        where S: serde::Serializer
+    {
+        // the defaults are written only if they are set, like the optional fields of
+        // the patterns
+        loop {
+            self.state += 1;
+            match self.state {
+                1 => {
+                    if self.value.whitespace_tolerant() {
+                        return serializer.serialize_struct_elt("whitespace_tolerant", true)
+                                         .map(Some);
+                    }
+                }
+                2 => {
+                    if self.value.strict_test_messages() {
+                        return serializer.serialize_struct_elt("strict_test_messages", true)
+                                         .map(Some);
+                    }
+                }
+                3 => {
+                    return serializer.serialize_struct_elt("patterns", self.value.patterns())
+                                     .map(Some)
+                }
+                _ => return Ok(None),
+            }
+        }
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(1 + self.value.whitespace_tolerant() as usize +
+             self.value.strict_test_messages() as usize)
+    }
+}
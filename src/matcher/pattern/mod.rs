@@ -5,6 +5,7 @@ pub use self::source::PatternSource;
 mod test;
 mod pattern;
 mod deser;
+mod ser;
 pub mod source;
 pub mod file;
 pub mod testmessage;
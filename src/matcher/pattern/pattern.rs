@@ -1,7 +1,7 @@
 use uuid::Uuid;
 use serde_json;
 
-use matcher::compiled_pattern::{CompiledPattern, TokenType};
+use matcher::compiled_pattern::{self, CompiledPattern, FormatError, TokenType};
 use super::testmessage::TestMessage;
 
 use std::borrow::Borrow;
@@ -15,6 +15,9 @@ pub struct Pattern {
     values: Option<BTreeMap<String, String>>,
     tags: Option<Vec<String>>,
     test_messages: Option<Vec<TestMessage>>,
+    case_insensitive: bool,
+    whitespace_tolerant: Option<bool>,
+    priority: i32,
 }
 
 impl Pattern {
@@ -36,6 +39,9 @@ impl Pattern {
             values: values,
             tags: tags,
             test_messages: test_messages,
+            case_insensitive: false,
+            whitespace_tolerant: None,
+            priority: 0,
         }
     }
 
@@ -63,6 +69,21 @@ impl Pattern {
         self.tags.as_ref().map(|tags| tags.borrow())
     }
 
+    pub fn test_messages(&self) -> Option<&[TestMessage]> {
+        self.test_messages.as_ref().map(|messages| messages.borrow())
+    }
+
+    // the test messages which don't decide whether they are strict inherit it
+    pub fn set_strict_test_messages(&mut self, strict: bool) {
+        if let Some(ref mut test_messages) = self.test_messages {
+            for test_message in test_messages.iter_mut() {
+                if test_message.strict().is_none() {
+                    test_message.set_strict(strict);
+                }
+            }
+        }
+    }
+
     pub fn from_json(doc: &str) -> Result<Pattern, serde_json::error::Error> {
         serde_json::from_str::<Pattern>(doc)
     }
@@ -71,6 +92,64 @@ impl Pattern {
         self.pattern = pattern;
     }
 
+    // the pattern as it's written in a pattern file, the literal options aren't
+    // applied yet (the matchers apply them and consume the tokens when the pattern is
+    // added to them)
+    pub fn to_pattern_string(&self) -> Result<String, FormatError> {
+        compiled_pattern::to_pattern_string(&self.pattern)
+    }
+
+    pub fn case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    pub fn set_case_insensitive(&mut self, case_insensitive: bool) {
+        self.case_insensitive = case_insensitive;
+    }
+
+    // it's None if the pattern doesn't decide, so it can be inherited from the pattern file
+    pub fn whitespace_tolerant(&self) -> Option<bool> {
+        self.whitespace_tolerant
+    }
+
+    pub fn set_whitespace_tolerant(&mut self, whitespace_tolerant: bool) {
+        self.whitespace_tolerant = Some(whitespace_tolerant);
+    }
+
+    // Replaces the literals according to case_insensitive and whitespace_tolerant. The
+    // matchers call it once when the pattern is added to them, so the options can be
+    // changed freely until then.
+    pub fn apply_literal_options(&mut self) {
+        let mut pattern = ::std::mem::replace(&mut self.pattern, Vec::new());
+        if self.case_insensitive {
+            pattern = compiled_pattern::ignore_literal_case(pattern);
+        }
+        if self.whitespace_tolerant == Some(true) {
+            pattern = compiled_pattern::tolerate_whitespace(pattern);
+        }
+        self.pattern = pattern;
+    }
+
+    // if more patterns match a message, the one with the highest priority wins
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    // A lower bound of the memory used by the pattern in bytes: the size of the
+    // struct and the bytes of its name, values and tags. The heap memory of the
+    // tokens, the parsers and the test messages isn't counted.
+    pub fn min_memory(&self) -> usize {
+        let strings = self.name.as_ref().map_or(0, |name| name.len()) +
+                      self.values.as_ref().map_or(0, |values| {
+            values.iter().map(|(key, value)| key.len() + value.len()).sum()
+        }) + self.tags.as_ref().map_or(0, |tags| tags.iter().map(|tag| tag.len()).sum());
+        ::std::mem::size_of::<Pattern>() + strings
+    }
+
     pub fn pop_first_token(&mut self) -> Option<TokenType> {
         if self.pattern.is_empty() {
             None
@@ -0,0 +1,85 @@
+use super::Pattern;
+
+use serde;
+
+// The optional fields are written only if they differ from their defaults, so a
+// deserialized pattern is written back in the same form.
+impl serde::Serialize for Pattern {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        let pattern = match self.to_pattern_string() {
+            Ok(pattern) => pattern,
+            Err(error) => return Err(serde::ser::Error::custom(error.to_string())),
+        };
+        serializer.serialize_struct("Pattern",
+                                    PatternMapVisitor {
+                                        value: self,
+                                        pattern: pattern,
+                                        state: 0,
+                                    })
+    }
+}
+
+struct PatternMapVisitor<'a> {
+    value: &'a Pattern,
+    pattern: String,
+    state: u8,
+}
+
+impl<'a> serde::ser::MapVisitor for PatternMapVisitor<'a> {
+    fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+        where S: serde::Serializer
+    {
+        loop {
+            self.state += 1;
+            match self.state {
+                1 => {
+                    if let Some(name) = self.value.name() {
+                        return serializer.serialize_struct_elt("name", name).map(Some);
+                    }
+                }
+                2 => {
+                    let uuid = self.value.uuid().hyphenated().to_string();
+                    return serializer.serialize_struct_elt("uuid", uuid).map(Some);
+                }
+                3 => return serializer.serialize_struct_elt("pattern", &self.pattern).map(Some),
+                4 => {
+                    if let Some(values) = self.value.values() {
+                        return serializer.serialize_struct_elt("values", values).map(Some);
+                    }
+                }
+                5 => {
+                    if let Some(tags) = self.value.tags() {
+                        return serializer.serialize_struct_elt("tags", tags).map(Some);
+                    }
+                }
+                6 => {
+                    if let Some(test_messages) = self.value.test_messages() {
+                        return serializer.serialize_struct_elt("test_messages", test_messages)
+                                         .map(Some);
+                    }
+                }
+                7 => {
+                    if self.value.case_insensitive() {
+                        return serializer.serialize_struct_elt("case_insensitive", true).map(Some);
+                    }
+                }
+                8 => {
+                    if let Some(whitespace_tolerant) = self.value.whitespace_tolerant() {
+                        return serializer.serialize_struct_elt("whitespace_tolerant",
+                                                               whitespace_tolerant)
+                                         .map(Some);
+                    }
+                }
+                9 => {
+                    if self.value.priority() != 0 {
+                        return serializer.serialize_struct_elt("priority", self.value.priority())
+                                         .map(Some);
+                    }
+                }
+                _ => return Ok(None),
+            }
+        }
+    }
+}
@@ -1,6 +1,12 @@
 use super::Pattern;
+use super::file::PatternFile;
+use matcher::compiled_pattern::TokenType;
+use parsers::SetParser;
 use uuid::Uuid;
 
+use serde_json;
+use serde_yaml;
+
 #[test]
 fn test_given_json_document_when_it_does_not_contain_errors_then_pattern_can_be_created_from_it
                                                                                                 () {
@@ -163,3 +169,195 @@ fn test_invalid_uuid_wont_make_uuid_library_panic_during_deserialization() {
 
     let _ = Pattern::from_json(buffer).err().expect("We should not panic when 'uuid' field is invalid");
 }
+
+#[test]
+fn test_given_json_pattern_when_it_is_case_insensitive_then_its_literals_are_replaced_by_parsers() {
+    let buffer = r#"
+{
+  "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+  "pattern": "Error: %{INT:code}",
+  "case_insensitive": true
+}
+"#;
+
+    let mut pattern = Pattern::from_json(buffer).expect("Failed to deserialize a JSON Pattern");
+    assert_eq!(pattern.pattern().len(), 2);
+    pattern.apply_literal_options();
+    assert_eq!(pattern.pattern().len(), 3);
+    match pattern.pattern()[0] {
+        TokenType::Parser(ref parser) => {
+            assert_eq!(parser.parse("ERROR: 1").map(|res| res.value()), Some("ERROR:"))
+        }
+        TokenType::Literal(..) => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_json_pattern_when_it_is_whitespace_tolerant_then_the_whitespace_runs_are_replaced_by_parsers
+    () {
+    let buffer = r#"
+{
+  "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+  "pattern": "Error:  disk %{INT:code}",
+  "case_insensitive": true,
+  "whitespace_tolerant": true
+}
+"#;
+
+    let mut pattern = Pattern::from_json(buffer).expect("Failed to deserialize a JSON Pattern");
+    assert_eq!(pattern.whitespace_tolerant(), Some(true));
+    pattern.apply_literal_options();
+    assert_eq!(pattern.pattern().len(), 5);
+    for token in pattern.pattern() {
+        if let TokenType::Literal(ref literal) = *token {
+            panic!("Unexpected literal: {:?}", literal);
+        }
+    }
+    match pattern.pattern()[1] {
+        TokenType::Parser(ref parser) => {
+            assert_eq!(parser.parse(" \t x").map(|res| res.value()), Some(" \t "))
+        }
+        TokenType::Literal(..) => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_pattern_when_its_literal_options_are_changed_then_only_the_last_values_are_applied() {
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(::grammar::parser::pattern("Error:  disk").unwrap());
+    pattern.set_whitespace_tolerant(true);
+    pattern.set_case_insensitive(true);
+    pattern.set_whitespace_tolerant(false);
+    pattern.apply_literal_options();
+    assert_eq!(pattern.pattern().len(), 3);
+    match pattern.pattern()[1] {
+        TokenType::Literal(ref literal) => assert_eq!(literal, "  "),
+        TokenType::Parser(..) => unreachable!(),
+    }
+    match pattern.pattern()[2] {
+        TokenType::Parser(ref parser) => {
+            assert_eq!(parser.parse("DISK").map(|res| res.value()), Some("DISK"))
+        }
+        TokenType::Literal(..) => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_json_pattern_when_it_has_a_priority_then_it_is_deserialized() {
+    let buffer = r#"
+{
+  "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+  "pattern": "Error: %{INT:code}",
+  "priority": -10
+}
+"#;
+
+    let pattern = Pattern::from_json(buffer).expect("Failed to deserialize a JSON Pattern");
+    assert_eq!(pattern.priority(), -10);
+    assert_eq!(Pattern::with_random_uuid().priority(), 0);
+}
+
+#[test]
+fn test_given_pattern_when_it_is_serialized_then_it_is_written_in_canonical_form() {
+    let buffer = r#"
+{
+  "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+  "pattern": "Error  \\%\\{ %{SET(min_len=2, 'a\"b'):set} %{INT(max_len=3,min_len=1):code}",
+  "case_insensitive": true,
+  "whitespace_tolerant": true,
+  "priority": 3,
+  "test_messages": [
+    {
+      "message": "ERROR %{ a\"b 12",
+      "values": {"code": 12, "set": "a\"b"}
+    }
+  ]
+}
+"#;
+
+    let pattern = Pattern::from_json(buffer).expect("Failed to deserialize a JSON Pattern");
+    assert_eq!(pattern.to_pattern_string().unwrap(),
+               r#"Error  \%\{ %{SET('"ab',min_len=2):set} %{INT(max_len=3,min_len=1):code}"#);
+    let json = serde_json::to_string(&pattern).unwrap();
+    assert_eq!(json,
+               r#"{"uuid":"9a49c47d-29e9-4072-be84-3b76c6814743","pattern":"Error  \\%\\{ %{SET('\"ab',min_len=2):set} %{INT(max_len=3,min_len=1):code}","test_messages":[{"message":"ERROR %{ a\"b 12","values":{"code":12,"set":"a\"b"}}],"case_insensitive":true,"whitespace_tolerant":true,"priority":3}"#);
+
+    let pattern = Pattern::from_json(&json).expect("Failed to deserialize a serialized Pattern");
+    assert_eq!(serde_json::to_string(&pattern).unwrap(), json);
+}
+
+#[test]
+fn test_given_pattern_file_when_it_is_serialized_to_yaml_then_it_can_be_deserialized_again() {
+    let buffer = r#"
+patterns:
+  - name: "SSH_DISCONNECT"
+    uuid: "9a49c47d-29e9-4072-be84-3b76c6814743"
+    pattern: "Received disconnect from %{GREEDY:ipaddr}: %{INT:dunno}"
+    values:
+      foo: "bar"
+    tags:
+      - "ssh"
+    test_messages:
+      - message: "Received disconnect from 1.2.3.4: 11"
+        values:
+          ipaddr: "1.2.3.4"
+        tags:
+          - "ssh"
+"#;
+
+    let file = serde_yaml::from_str::<PatternFile>(buffer).unwrap();
+    let yaml = serde_yaml::to_string(&file).unwrap();
+    let file = serde_yaml::from_str::<PatternFile>(&yaml).unwrap();
+    assert_eq!(serde_yaml::to_string(&file).unwrap(), yaml);
+    assert!(!yaml.contains("whitespace_tolerant"));
+    let pattern = &file.patterns()[0];
+    assert_eq!(pattern.name(), Some("SSH_DISCONNECT"));
+    assert_eq!(pattern.to_pattern_string().unwrap(),
+               "Received disconnect from %{GREEDY:ipaddr}: %{INT:dunno}");
+    assert_eq!(pattern.test_messages().unwrap()[0].tags(),
+               Some(&["ssh".to_owned()][..]));
+}
+
+#[test]
+fn test_given_pattern_file_with_defaults_when_it_is_serialized_then_the_defaults_are_kept() {
+    let buffer = r#"{"whitespace_tolerant":true,"strict_test_messages":true,"patterns":[{"uuid":"9a49c47d-29e9-4072-be84-3b76c6814743","pattern":"Error: %{INT:code}","test_messages":[{"message":"Error: 12"}]}]}"#;
+
+    let file = serde_json::from_str::<PatternFile>(buffer).unwrap();
+    assert_eq!(file.whitespace_tolerant(), true);
+    assert_eq!(file.strict_test_messages(), true);
+    assert_eq!(serde_json::to_string(&file).unwrap(), buffer);
+
+    let pattern = file.into_iter().next().unwrap().unwrap();
+    assert_eq!(pattern.whitespace_tolerant(), Some(true));
+    assert_eq!(pattern.test_messages().unwrap()[0].strict(), Some(true));
+}
+
+#[test]
+fn test_given_pattern_when_a_parser_string_contains_both_kinds_of_quotes_then_it_cannot_be_serialized
+    () {
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(vec![TokenType::Literal("set: ".to_owned()),
+                             TokenType::Parser(Box::new(SetParser::new("'\"")))]);
+    assert_eq!(pattern.to_pattern_string().is_err(), true);
+    assert_eq!(serde_json::to_string(&pattern).is_err(), true);
+}
+
+#[test]
+fn test_given_case_insensitive_pattern_when_it_is_serialized_then_its_literals_keep_their_case() {
+    let buffer = r#"{"uuid":"9a49c47d-29e9-4072-be84-3b76c6814743","pattern":"Disk  Error: %{ICASE(\"Code\")} %{INT:code}","case_insensitive":true,"whitespace_tolerant":true}"#;
+
+    let pattern = Pattern::from_json(buffer).unwrap();
+    assert_eq!(serde_json::to_string(&pattern).unwrap(), buffer);
+}
+
+#[test]
+fn test_given_pattern_with_non_ascii_set_when_it_is_serialized_then_the_set_is_kept() {
+    let buffer = r#"{"uuid":"9a49c47d-29e9-4072-be84-3b76c6814743","pattern":"név: %{SET(\"áéíóöúüőű\"):name}"}"#;
+
+    let pattern = Pattern::from_json(buffer).unwrap();
+    let json = serde_json::to_string(&pattern).unwrap();
+    assert_eq!(json, buffer);
+    let pattern = Pattern::from_json(&json).unwrap();
+    assert_eq!(serde_json::to_string(&pattern).unwrap(), json);
+}
+
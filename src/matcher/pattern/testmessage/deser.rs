@@ -1,4 +1,7 @@
-use super::TestMessage;
+use std::collections::BTreeMap;
+
+use super::{TestMessage, Expectation};
+use parsers::TypedValue;
 use serde;
 
 impl serde::Deserialize for TestMessage {
@@ -13,6 +16,8 @@ enum Field {
     MESSAGE,
     VALUES,
     TAGS,
+    EXPECT,
+    STRICT,
 }
 
 impl serde::Deserialize for Field {
@@ -31,6 +36,8 @@ impl serde::Deserialize for Field {
                     "message" => Ok(Field::MESSAGE),
                     "values" => Ok(Field::VALUES),
                     "tags" => Ok(Field::TAGS),
+                    "expect" => Ok(Field::EXPECT),
+                    "strict" => Ok(Field::STRICT),
                     _ => Err(serde::de::Error::custom(format!("Unexpected field: {}", value))),
                 }
             }
@@ -52,12 +59,21 @@ impl serde::de::Visitor for TestMessageVisitor {
         let mut message = None;
         let mut values = None;
         let mut tags = None;
+        let mut expectation = Expectation::Match;
+        let mut strict: Option<bool> = None;
 
         while let Some(field) = try!(visitor.visit_key()) {
             match field {
                 Field::MESSAGE => message = Some(try!(visitor.visit_value())),
                 Field::VALUES => values = Some(try!(visitor.visit_value())),
                 Field::TAGS => tags = Some(try!(visitor.visit_value())),
+                Field::STRICT => strict = Some(try!(visitor.visit_value())),
+                Field::EXPECT => {
+                    let name: String = try!(visitor.visit_value());
+                    expectation = try!(Expectation::from_name(&name).ok_or_else(|| {
+                        serde::de::Error::custom(format!("Invalid field 'expect': {}", name))
+                    }));
+                }
             }
         }
 
@@ -66,10 +82,28 @@ impl serde::de::Visitor for TestMessageVisitor {
             None => try!(visitor.missing_field("message")),
         };
 
-        let values = values.unwrap_or_default();
+        let mut string_values = BTreeMap::new();
+        let mut typed_values = BTreeMap::new();
+        let values: BTreeMap<String, TypedValue> = values.unwrap_or_default();
+        for (key, value) in values {
+            match value {
+                TypedValue::String(value) => {
+                    string_values.insert(key, value);
+                }
+                value => {
+                    typed_values.insert(key, value);
+                }
+            }
+        }
 
         try!(visitor.end());
 
-        Ok(TestMessage::new(message_final, values, tags))
+        let mut test_message = TestMessage::new(message_final, string_values, tags);
+        test_message.set_typed_values(typed_values);
+        test_message.set_expectation(expectation);
+        if let Some(strict) = strict {
+            test_message.set_strict(strict);
+        }
+        Ok(test_message)
     }
 }
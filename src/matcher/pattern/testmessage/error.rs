@@ -31,6 +31,16 @@ pub enum Error {
         expected: Option<Vec<String>>,
         got: Option<Vec<String>>,
     },
+    UnexpectedMatch {
+        pattern_uuid: String,
+        got_uuid: String,
+        message: String,
+    },
+    UnexpectedKey {
+        pattern_uuid: String,
+        key: String,
+        value: String,
+    },
 }
 
 impl Error {
@@ -82,6 +92,24 @@ impl Error {
             got: got,
         }
     }
+
+    // a negative test message of the pattern matched to the got pattern
+    pub fn unexpected_match(pattern_uuid: &Uuid, got_uuid: &Uuid, test_message: &str) -> Error {
+        Error::UnexpectedMatch {
+            pattern_uuid: pattern_uuid.hyphenated().to_string(),
+            got_uuid: got_uuid.hyphenated().to_string(),
+            message: test_message.to_owned(),
+        }
+    }
+
+    // a strict test message doesn't expect the key extracted by a parser
+    pub fn unexpected_key(pattern_uuid: &Uuid, key: &str, value: &str) -> Error {
+        Error::UnexpectedKey {
+            pattern_uuid: pattern_uuid.hyphenated().to_string(),
+            key: key.to_owned(),
+            value: value.to_owned(),
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -122,6 +150,20 @@ impl fmt::Display for Error {
                                            expected,
                                            got))
             }
+            Error::UnexpectedMatch{ref pattern_uuid, ref got_uuid, ref message} => {
+                fmt.write_fmt(format_args!("A test message which must not match matched to a \
+                                            pattern: uuid={} got_uuid={} test_message='{}'",
+                                           pattern_uuid,
+                                           got_uuid,
+                                           message))
+            }
+            Error::UnexpectedKey{ref pattern_uuid, ref key, ref value} => {
+                fmt.write_fmt(format_args!("A parsed key is not found among the expected ones of \
+                                            a strict test message: uuid={} key={} value={}",
+                                           pattern_uuid,
+                                           key,
+                                           value))
+            }
         }
     }
 }
@@ -144,6 +186,12 @@ impl error::Error for Error {
             Error::UnexpectedTags{..} => {
                 "Unexpected tags found either in the parse result or among the expected ones"
             }
+            Error::UnexpectedMatch{..} => {
+                "A test message which must not match matched to a pattern"
+            }
+            Error::UnexpectedKey{..} => {
+                "A parsed key is not found among the expected ones of a strict test message"
+            }
         }
     }
 }
@@ -2,13 +2,48 @@ use std::collections::BTreeMap;
 use std::borrow::Borrow;
 
 use matcher::result::MatchResult;
+use parsers::TypedValue;
 use super::Error;
 
+// what the test message expects from the matcher, the negative test messages prevent
+// the patterns from becoming too broad
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Expectation {
+    // it must match its pattern with the expected values and tags
+    Match,
+    // it must not match its pattern
+    NoMatch,
+    // it must not match any pattern
+    NoMatchAny,
+}
+
+impl Expectation {
+    pub fn from_name(name: &str) -> Option<Expectation> {
+        match name {
+            "match" => Some(Expectation::Match),
+            "no_match" => Some(Expectation::NoMatch),
+            "no_match_any" => Some(Expectation::NoMatchAny),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Expectation::Match => "match",
+            Expectation::NoMatch => "no_match",
+            Expectation::NoMatchAny => "no_match_any",
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TestMessage {
     message: String,
     values: BTreeMap<String, String>,
+    typed_values: BTreeMap<String, TypedValue>,
     tags: Option<Vec<String>>,
+    expectation: Expectation,
+    strict: Option<bool>,
 }
 
 impl TestMessage {
@@ -19,7 +54,10 @@ impl TestMessage {
         TestMessage {
             message: message,
             values: values,
+            typed_values: BTreeMap::new(),
             tags: tags,
+            expectation: Expectation::Match,
+            strict: None,
         }
     }
 
@@ -31,13 +69,73 @@ impl TestMessage {
         &self.values
     }
 
+    // the expected values which are not strings, they are compared with the typed
+    // values of the match result
+    pub fn typed_values(&self) -> &BTreeMap<String, TypedValue> {
+        &self.typed_values
+    }
+
+    pub fn set_typed_values(&mut self, typed_values: BTreeMap<String, TypedValue>) {
+        self.typed_values = typed_values;
+    }
+
     pub fn tags(&self) -> Option<&[String]> {
         self.tags.as_ref().map(|x| x.borrow())
     }
 
+    pub fn expectation(&self) -> Expectation {
+        self.expectation
+    }
+
+    pub fn set_expectation(&mut self, expectation: Expectation) {
+        self.expectation = expectation;
+    }
+
+    // A strict test message fails if the parsers extracted a key which it doesn't
+    // expect or if the pattern has a tag which it doesn't expect. It's None if the test
+    // message doesn't decide, so it can be inherited from the pattern file.
+    pub fn strict(&self) -> Option<bool> {
+        self.strict
+    }
+
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = Some(strict);
+    }
+
     pub fn test_result(&self, result: &MatchResult) -> Result<(), Error> {
         try!(self.test_tags(result));
-        self.test_values(result)
+        try!(self.test_values(result));
+        if self.strict == Some(true) {
+            try!(self.test_no_unexpected_tags(result));
+            try!(self.test_no_unexpected_keys(result));
+        }
+        Ok(())
+    }
+
+    // the values of the pattern are not extracted by the parsers, so they aren't checked
+    fn test_no_unexpected_keys(&self, result: &MatchResult) -> Result<(), Error> {
+        let extracted = result.values()
+                              .iter()
+                              .map(|(key, value)| (*key, *value))
+                              .chain(result.dynamic_values()
+                                           .iter()
+                                           .map(|(key, value)| (&key[..], &value[..])));
+        for (key, value) in extracted {
+            if !self.values.contains_key(key) && !self.typed_values.contains_key(key) {
+                return Err(Error::unexpected_key(result.pattern().uuid(), key, value));
+            }
+        }
+        Ok(())
+    }
+
+    fn test_no_unexpected_tags(&self, result: &MatchResult) -> Result<(), Error> {
+        if let Some(got_tags) = result.pattern().tags() {
+            let expected_tags = self.tags().unwrap_or(&[]);
+            if got_tags.iter().any(|tag| !expected_tags.contains(tag)) {
+                return Err(self.report_unexpected_tags_error(result));
+            }
+        }
+        Ok(())
     }
 
     fn test_values(&self, result: &MatchResult) -> Result<(), Error> {
@@ -46,9 +144,42 @@ impl TestMessage {
         for (key, value) in self.values() {
             try!(TestMessage::test_value(key, value, &merged_values, result));
         }
+        for (key, value) in self.typed_values() {
+            try!(TestMessage::test_typed_value(key, value, &merged_values, result));
+        }
         Ok(())
     }
 
+    fn test_typed_value(key: &str,
+                        value: &TypedValue,
+                        values: &BTreeMap<&str, &str>,
+                        result: &MatchResult)
+                        -> Result<(), Error> {
+        let got_value = match result.typed_value(key) {
+            Some(Ok(got_value)) => got_value,
+            Some(Err(err)) => {
+                let expected = format!("{} ({})", value, value.value_type().name());
+                return Err(Error::value_not_match(result.pattern().uuid(),
+                                                  key,
+                                                  &expected,
+                                                  &err.to_string()));
+            }
+            None => {
+                match values.get(key) {
+                    Some(got_value) => TypedValue::String((*got_value).to_owned()),
+                    None => return Err(Error::key_not_found(result.pattern().uuid(), key)),
+                }
+            }
+        };
+        if *value == got_value {
+            Ok(())
+        } else {
+            let expected = format!("{} ({})", value, value.value_type().name());
+            let got = format!("{} ({})", got_value, got_value.value_type().name());
+            Err(Error::value_not_match(result.pattern().uuid(), key, &expected, &got))
+        }
+    }
+
     fn test_value(key: &str,
                   value: &str,
                   values: &BTreeMap<&str, &str>,
@@ -77,6 +208,9 @@ impl TestMessage {
         for (key, value) in result.values() {
             merged_values.insert(key, value);
         }
+        for (key, value) in result.dynamic_values() {
+            merged_values.insert(key.borrow(), value.borrow());
+        }
 
         merged_values
     }
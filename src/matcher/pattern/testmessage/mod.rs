@@ -1,8 +1,9 @@
 pub use self::error::Error;
-pub use self::message::TestMessage;
+pub use self::message::{TestMessage, Expectation};
 
 #[cfg(test)]
 mod test;
 mod deser;
+mod ser;
 mod error;
 mod message;
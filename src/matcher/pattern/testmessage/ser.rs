@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use super::{TestMessage, Expectation};
+use parsers::TypedValue;
+use serde;
+
+impl serde::Serialize for TestMessage {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        serializer.serialize_struct("TestMessage",
+                                    TestMessageMapVisitor {
+                                        value: self,
+                                        state: 0,
+                                    })
+    }
+}
+
+struct TestMessageMapVisitor<'a> {
+    value: &'a TestMessage,
+    state: u8,
+}
+
+impl<'a> TestMessageMapVisitor<'a> {
+    // the string and the typed values are written into the same object
+    fn values(&self) -> BTreeMap<&'a str, TypedValue> {
+        let mut values = BTreeMap::new();
+        for (key, value) in self.value.values() {
+            values.insert(&key[..], TypedValue::String(value.clone()));
+        }
+        for (key, value) in self.value.typed_values() {
+            values.insert(&key[..], value.clone());
+        }
+        values
+    }
+}
+
+impl<'a> serde::ser::MapVisitor for TestMessageMapVisitor<'a> {
+    fn visit<S>(&mut self, serializer: &mut S) -> Result<Option<()>, S::Error>
+        where S: serde::Serializer
+    {
+        loop {
+            self.state += 1;
+            match self.state {
+                1 => {
+                    return serializer.serialize_struct_elt("message", self.value.message())
+                                     .map(Some)
+                }
+                2 => {
+                    let values = self.values();
+                    if !values.is_empty() {
+                        return serializer.serialize_struct_elt("values", values).map(Some);
+                    }
+                }
+                3 => {
+                    if let Some(tags) = self.value.tags() {
+                        return serializer.serialize_struct_elt("tags", tags).map(Some);
+                    }
+                }
+                4 => {
+                    if self.value.expectation() != Expectation::Match {
+                        return serializer.serialize_struct_elt("expect",
+                                                               self.value.expectation().name())
+                                         .map(Some);
+                    }
+                }
+                5 => {
+                    if let Some(strict) = self.value.strict() {
+                        return serializer.serialize_struct_elt("strict", strict).map(Some);
+                    }
+                }
+                _ => return Ok(None),
+            }
+        }
+    }
+}
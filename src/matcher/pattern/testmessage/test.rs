@@ -1,4 +1,8 @@
-use super::TestMessage;
+use super::{TestMessage, Error};
+use matcher::Pattern;
+use matcher::result::MatchResult;
+use parsers::{IntParser, ParseResult};
+use uuid::Uuid;
 use serde_json;
 use std::borrow::Borrow;
 
@@ -80,3 +84,55 @@ fn test_given_json_test_message_when_it_contains_not_just_the_valid_fields_then_
     let _ = result.err().expect("Failed to return an error when a serialized TestMessage \
                                  contains non-valid fields");
 }
+
+#[test]
+fn test_given_strict_test_message_when_the_pattern_has_an_unexpected_tag_then_the_test_fails() {
+    let buffer = r#"
+{
+"message": "disk is full",
+"tags": ["disk"],
+"strict": true
+}
+"#;
+    let msg = serde_json::from_str::<TestMessage>(buffer).unwrap();
+    let pattern = Pattern::new(None,
+                               Uuid::new_v4(),
+                               Vec::new(),
+                               None,
+                               None,
+                               Some(vec!["disk".to_owned(), "full".to_owned()]));
+    let result = MatchResult::new(&pattern);
+    match msg.test_result(&result) {
+        Err(Error::UnexpectedTags{..}) => (),
+        result => panic!("Unexpected result: {:?}", result),
+    }
+
+    let mut msg = msg.clone();
+    msg.set_strict(false);
+    msg.test_result(&result).unwrap();
+}
+
+#[test]
+fn test_given_test_message_with_typed_value_when_the_value_cannot_be_converted_then_the_test_fails
+    () {
+    let buffer = r#"
+{
+"message": "pid=99999999999999999999",
+"values": {
+"pid": 1
+}
+}
+"#;
+    let msg = serde_json::from_str::<TestMessage>(buffer).unwrap();
+    let parser = IntParser::with_name("pid".to_owned());
+    let pattern = Pattern::with_random_uuid();
+    let mut result = MatchResult::new(&pattern);
+    result.insert(ParseResult::new(&parser, "99999999999999999999"));
+    match msg.test_result(&result) {
+        Err(Error::ValueNotMatch{ref got_value, ..}) => {
+            assert_eq!(got_value,
+                       "Value cannot be converted: type=int value=99999999999999999999")
+        }
+        result => panic!("Unexpected result: {:?}", result),
+    }
+}
@@ -1,4 +1,4 @@
-use super::pattern::file::PatternFile;
+use super::pattern::file::{FileFormat, PatternFile};
 use matcher::BuildError;
 use matcher::MatcherFactory;
 use matcher::FromPatternSource;
@@ -13,11 +13,9 @@ use serde_yaml;
 pub struct PatternLoader;
 
 impl PatternLoader {
-    fn read(pattern_file_path: &str) -> Result<String, BuildError>
-    {
+    fn read<R: Read>(reader: &mut R) -> Result<String, BuildError> {
         let mut buffer = String::new();
-        let mut file = try!(File::open(pattern_file_path));
-        let _ = file.read_to_string(&mut buffer);
+        try!(reader.read_to_string(&mut buffer));
         Ok(buffer)
     }
 
@@ -35,26 +33,49 @@ impl PatternLoader {
         F::Matcher::from_source::<F>(&mut file.into_iter())
     }
 
+    pub fn from_str<F>(content: &str, format: FileFormat) -> Result<F::Matcher, BuildError>
+        where F: MatcherFactory
+    {
+        let file = try!(PatternLoader::load_str(content, format));
+        F::Matcher::from_source::<F>(&mut file.into_iter())
+    }
+
+    pub fn from_reader<F, R>(reader: &mut R, format: FileFormat) -> Result<F::Matcher, BuildError>
+        where F: MatcherFactory,
+              R: Read
+    {
+        let file = try!(PatternLoader::load_reader(reader, format));
+        F::Matcher::from_source::<F>(&mut file.into_iter())
+    }
+
+    // The format is chosen by the extension of the file. When the file doesn't have a
+    // known extension, it's detected from the content, if it's neither a JSON object nor
+    // a YAML mapping, UnknownFileFormat is returned.
     pub fn load_file(pattern_file_path: &str) -> Result<PatternFile, BuildError>
     {
         let path = path::Path::new(pattern_file_path);
-        match path.extension() {
+        let format = match path.extension() {
             Some(extension) => {
-                match try!(extension.to_str().ok_or(BuildError::NotUtf8FileName)) {
-                    "json" => {
-                        let content = try!(PatternLoader::read(pattern_file_path));
-                        let file = try!(serde_json::from_str::<PatternFile>(&content));
-                        Ok(file)
-                    },
-                    "yaml" | "yml" | "YAML" | "YML" => {
-                        let content = try!(PatternLoader::read(pattern_file_path));
-                        let file = try!(serde_yaml::from_str::<PatternFile>(&content));
-                        Ok(file)
-                    },
-                    _ => Err(BuildError::UnsupportedFileExtension),
-                }
+                let extension = try!(extension.to_str().ok_or(BuildError::NotUtf8FileName));
+                FileFormat::from_extension(extension)
             }
-            None => Err(BuildError::UnsupportedFileExtension),
+            None => None,
+        };
+        let content = try!(PatternLoader::read(&mut try!(File::open(path))));
+        let format = try!(format.or_else(|| FileFormat::detect(&content))
+                                .ok_or(BuildError::UnknownFileFormat));
+        PatternLoader::load_str(&content, format)
+    }
+
+    pub fn load_str(content: &str, format: FileFormat) -> Result<PatternFile, BuildError> {
+        match format {
+            FileFormat::Json => Ok(try!(serde_json::from_str::<PatternFile>(content))),
+            FileFormat::Yaml => Ok(try!(serde_yaml::from_str::<PatternFile>(content))),
         }
     }
+
+    pub fn load_reader<R: Read>(reader: &mut R, format: FileFormat) -> Result<PatternFile, BuildError> {
+        let content = try!(PatternLoader::read(reader));
+        PatternLoader::load_str(&content, format)
+    }
 }
@@ -12,7 +12,10 @@ pub enum BuildError {
     Io(io::Error),
     DeserJson(serde_json::Error),
     DeserYaml(serde_yaml::Error),
+    #[deprecated(note = "the format of a file with an unknown extension is detected from its \
+                         content, UnknownFileFormat is returned if it can't be detected")]
     UnsupportedFileExtension,
+    UnknownFileFormat,
     NotUtf8FileName,
 }
 
@@ -40,6 +43,8 @@ impl From<serde_yaml::Error> for BuildError {
     }
 }
 
+// it still handles the deprecated variants
+#[allow(deprecated)]
 impl fmt::Display for BuildError {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         match *self {
@@ -48,13 +53,17 @@ impl fmt::Display for BuildError {
             BuildError::DeserJson(ref error) => error.fmt(formatter),
             BuildError::DeserYaml(ref error) => error.fmt(formatter),
             BuildError::UnsupportedFileExtension =>
-                formatter.write_fmt(format_args!("The given file extension is not suppoted")),
+                formatter.write_str("The given file extension is not supported"),
+            BuildError::UnknownFileFormat =>
+                formatter.write_str("The format of the pattern file is unknown, it's neither a \
+                                     JSON object nor a YAML mapping"),
             BuildError::NotUtf8FileName =>
                 formatter.write_str("The given filename contains non Utf-8 characters"),
         }
     }
 }
 
+#[allow(deprecated)]
 impl error::Error for BuildError {
     fn description(&self) -> &str {
         match *self {
@@ -63,6 +72,7 @@ impl error::Error for BuildError {
             BuildError::DeserJson(ref error) => error.description(),
             BuildError::DeserYaml(ref error) => error.description(),
             BuildError::UnsupportedFileExtension => "The given file extension is not supported",
+            BuildError::UnknownFileFormat => "The format of the pattern file is unknown",
             BuildError::NotUtf8FileName => "The given filename contains non Utf-8 characters",
         }
     }
@@ -73,7 +83,9 @@ impl error::Error for BuildError {
             BuildError::Io(ref error) => error.cause(),
             BuildError::DeserJson(ref error) => error.cause(),
             BuildError::DeserYaml(ref error) => error.cause(),
-            BuildError::UnsupportedFileExtension | BuildError::NotUtf8FileName => None,
+            BuildError::UnsupportedFileExtension |
+            BuildError::UnknownFileFormat |
+            BuildError::NotUtf8FileName => None,
         }
     }
 }
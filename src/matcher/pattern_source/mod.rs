@@ -1,27 +1,45 @@
 use uuid::Uuid;
 
 use matcher::pattern::{Pattern, PatternSource};
-use matcher::pattern::testmessage::{self, TestMessage};
+use matcher::pattern::testmessage::{self, TestMessage, Expectation};
 use matcher::{Matcher, MatcherFactory};
 use matcher::result::MatchResult;
 use matcher::pattern::source::BuildResult;
 pub use self::error::BuildError;
 
 mod error;
+pub mod runner;
 
 pub trait FromPatternSource {
+    // The test messages which must not match any pattern are checked again when every
+    // pattern is added, so the patterns added after them are also checked.
     fn from_source<F: MatcherFactory>(from: &mut PatternSource) -> Result<F::Matcher, BuildError> {
         let mut matcher = F::new_matcher();
+        let mut unmatched = Vec::new();
         for pattern in from {
-            try!(Self::check_pattern::<F::Matcher>(&mut matcher, pattern));
+            try!(Self::check_pattern_collecting_unmatched::<F::Matcher>(&mut matcher,
+                                                                        pattern,
+                                                                        &mut unmatched));
+        }
+        for &(ref uuid, ref message) in &unmatched {
+            try!(Self::check_test_messages(&matcher, ::std::slice::from_ref(message), uuid));
         }
         Ok(matcher)
     }
 
     fn from_source_ignore_errors<F: MatcherFactory>(from: &mut PatternSource) -> F::Matcher {
         let mut matcher = F::new_matcher();
+        let mut unmatched = Vec::new();
         for pattern in from {
-            let result = Self::check_pattern::<F::Matcher>(&mut matcher, pattern);
+            let result = Self::check_pattern_collecting_unmatched::<F::Matcher>(&mut matcher,
+                                                                                pattern,
+                                                                                &mut unmatched);
+            if let Err(error) = result {
+                error!("{}", error);
+            }
+        }
+        for &(ref uuid, ref message) in &unmatched {
+            let result = Self::check_test_messages(&matcher, ::std::slice::from_ref(message), uuid);
             if let Err(error) = result {
                 error!("{}", error);
             }
@@ -30,12 +48,26 @@ pub trait FromPatternSource {
     }
 
     fn check_pattern<M: Matcher>(matcher: &mut M, result: BuildResult) -> Result<(), BuildError> {
+        Self::check_pattern_collecting_unmatched(matcher, result, &mut Vec::new())
+    }
+
+    // the test messages which must not match any pattern are also pushed into unmatched
+    fn check_pattern_collecting_unmatched<M: Matcher>(matcher: &mut M,
+                                                      result: BuildResult,
+                                                      unmatched: &mut Vec<(Uuid, TestMessage)>)
+                                                      -> Result<(), BuildError> {
         let mut pattern = try!(result);
         let uuid = pattern.uuid().to_owned();
         let test_messages = Self::extract_test_messages(&mut pattern);
         matcher.add_pattern(pattern);
         debug!("validating pattern: {}", uuid.hyphenated().to_string());
-        Self::check_test_messages(matcher, &test_messages, &uuid)
+        try!(Self::check_test_messages(matcher, &test_messages, &uuid));
+        for message in test_messages {
+            if message.expectation() == Expectation::NoMatchAny {
+                unmatched.push((uuid, message));
+            }
+        }
+        Ok(())
     }
 
     fn extract_test_messages(pattern: &mut Pattern) -> Vec<TestMessage> {
@@ -52,14 +84,44 @@ pub trait FromPatternSource {
                                        uuid: &Uuid)
                                        -> Result<(), BuildError> {
         for msg in messages {
-            let result = try!(matcher.parse(msg.message())
-                                     .ok_or(testmessage::Error::test_message_does_not_match(uuid,
-                                                                                            msg)));
-            try!(Self::check_test_message(msg, &result, uuid));
+            try!(Self::run_test_message(matcher, msg, uuid));
         }
         Ok(())
     }
 
+    // checks one test message of the pattern with the given UUID
+    fn run_test_message<M: Matcher>(matcher: &M,
+                                    msg: &TestMessage,
+                                    uuid: &Uuid)
+                                    -> Result<(), testmessage::Error> {
+        match msg.expectation() {
+            Expectation::Match => {
+                let error = testmessage::Error::test_message_does_not_match(uuid, msg);
+                let result = try!(matcher.parse(msg.message()).ok_or(error));
+                Self::check_test_message(msg, &result, uuid)
+            }
+            Expectation::NoMatch => {
+                if matcher.parse_all(msg.message())
+                          .iter()
+                          .any(|result| result.pattern().uuid() == uuid) {
+                    Err(testmessage::Error::unexpected_match(uuid, uuid, msg.message()))
+                } else {
+                    Ok(())
+                }
+            }
+            Expectation::NoMatchAny => {
+                match matcher.parse(msg.message()) {
+                    Some(result) => {
+                        Err(testmessage::Error::unexpected_match(uuid,
+                                                                 result.pattern().uuid(),
+                                                                 msg.message()))
+                    }
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+
     fn check_test_message(message: &TestMessage,
                           result: &MatchResult,
                           expected_uuid: &Uuid)
@@ -0,0 +1,385 @@
+use serde_json::{self, Value};
+use uuid::Uuid;
+
+use matcher::pattern::PatternSource;
+use matcher::pattern::testmessage::{self, TestMessage, Expectation};
+use matcher::{Matcher, MatcherFactory, FromPatternSource, BuildError};
+use matcher::coverage::CoverageReport;
+use matcher::dump::DumpNode;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct TestCase {
+    message: String,
+    expectation: Expectation,
+    duration: Duration,
+    error: Option<testmessage::Error>,
+    matched: Option<Uuid>,
+}
+
+impl TestCase {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn expectation(&self) -> Expectation {
+        self.expectation
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    pub fn error(&self) -> Option<&testmessage::Error> {
+        self.error.as_ref()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+
+    // the pattern which the message matched, it's recorded whatever was expected
+    pub fn matched(&self) -> Option<&Uuid> {
+        self.matched.as_ref()
+    }
+}
+
+#[derive(Debug)]
+pub struct PatternReport {
+    uuid: Uuid,
+    name: Option<String>,
+    cases: Vec<TestCase>,
+}
+
+impl PatternReport {
+    pub fn uuid(&self) -> &Uuid {
+        &self.uuid
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| &name[..])
+    }
+
+    pub fn cases(&self) -> &[TestCase] {
+        &self.cases
+    }
+
+    pub fn failures(&self) -> usize {
+        self.cases.iter().filter(|case| !case.passed()).count()
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.cases.iter().fold(Duration::new(0, 0), |sum, case| sum + case.duration)
+    }
+}
+
+// The results of the test messages grouped by their patterns in the order of the
+// pattern source. The patterns which couldn't be built are in errors.
+#[derive(Debug, Default)]
+pub struct TestReport {
+    patterns: Vec<PatternReport>,
+    errors: Vec<BuildError>,
+}
+
+impl TestReport {
+    pub fn patterns(&self) -> &[PatternReport] {
+        &self.patterns
+    }
+
+    pub fn errors(&self) -> &[BuildError] {
+        &self.errors
+    }
+
+    pub fn tests(&self) -> usize {
+        self.patterns.iter().map(|pattern| pattern.cases.len()).sum()
+    }
+
+    pub fn failures(&self) -> usize {
+        self.patterns.iter().map(|pattern| pattern.failures()).sum()
+    }
+
+    pub fn passed(&self) -> bool {
+        self.errors.is_empty() && self.failures() == 0
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.patterns.iter().fold(Duration::new(0, 0), |sum, pattern| sum + pattern.duration())
+    }
+
+    // root is the dump of the matcher returned by TestRunner::run()
+    pub fn coverage(&self, root: &DumpNode) -> CoverageReport {
+        let matched: BTreeSet<Uuid> = self.patterns
+                                          .iter()
+                                          .flat_map(|pattern| pattern.cases.iter())
+                                          .filter_map(|case| case.matched)
+                                          .collect();
+        let tested = self.patterns
+                         .iter()
+                         .filter(|pattern| !pattern.cases.is_empty())
+                         .map(|pattern| pattern.uuid)
+                         .collect();
+        CoverageReport::new(root, &matched, &tested)
+    }
+}
+
+// Checks the test messages like FromPatternSource::from_source() does, but it doesn't
+// stop at the first error: every test message is run and its result is recorded.
+pub struct TestRunner;
+
+impl TestRunner {
+    pub fn run<F: MatcherFactory>(from: &mut PatternSource) -> (F::Matcher, TestReport) {
+        let mut matcher = F::new_matcher();
+        let mut report = TestReport::default();
+        // they are run when every pattern is added, like in from_source()
+        let mut unmatched = Vec::new();
+
+        for pattern in from {
+            let mut pattern = match pattern {
+                Ok(pattern) => pattern,
+                Err(error) => {
+                    report.errors.push(error);
+                    continue;
+                }
+            };
+            let uuid = *pattern.uuid();
+            let name = pattern.name().map(|name| name.to_owned());
+            let mut test_messages = F::Matcher::extract_test_messages(&mut pattern);
+            // they are extracted from the last one, but they are reported in their order
+            test_messages.reverse();
+            matcher.add_pattern(pattern);
+
+            let mut cases = Vec::new();
+            for message in test_messages {
+                if message.expectation() == Expectation::NoMatchAny {
+                    unmatched.push((report.patterns.len(), message));
+                } else {
+                    cases.push(TestRunner::run_test_message(&matcher, &message, &uuid));
+                }
+            }
+            report.patterns.push(PatternReport {
+                uuid: uuid,
+                name: name,
+                cases: cases,
+            });
+        }
+
+        for (index, message) in unmatched {
+            let uuid = report.patterns[index].uuid;
+            let case = TestRunner::run_test_message(&matcher, &message, &uuid);
+            report.patterns[index].cases.push(case);
+        }
+        (matcher, report)
+    }
+
+    fn run_test_message<M: Matcher>(matcher: &M, message: &TestMessage, uuid: &Uuid) -> TestCase {
+        let start = Instant::now();
+        let result = M::run_test_message(matcher, message, uuid);
+        let duration = start.elapsed();
+        // the pattern is looked up again, so the coverage isn't part of the duration
+        let matched = matcher.parse(message.message()).map(|result| *result.pattern().uuid());
+        TestCase {
+            message: message.message().to_owned(),
+            expectation: message.expectation(),
+            duration: duration,
+            error: result.err(),
+            matched: matched,
+        }
+    }
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + duration.subsec_nanos() as f64 / 1e9
+}
+
+fn escape_xml(value: &str) -> String {
+    value.replace('&', "&amp;")
+         .replace('<', "&lt;")
+         .replace('>', "&gt;")
+         .replace('"', "&quot;")
+         .replace('\'', "&apos;")
+}
+
+// Every pattern is a test suite named after the pattern (or its UUID), every test
+// message is a test case. The patterns which couldn't be built are in the `errors`
+// test suite.
+pub fn write_junit<W: Write>(report: &TestReport, out: &mut W) -> io::Result<()> {
+    try!(writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+    try!(writeln!(out,
+                  "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.6}\">",
+                  report.tests(),
+                  report.failures(),
+                  report.errors().len(),
+                  seconds(report.duration())));
+    for pattern in report.patterns() {
+        let uuid = pattern.uuid().hyphenated().to_string();
+        try!(writeln!(out,
+                      "  <testsuite name=\"{}\" id=\"{}\" tests=\"{}\" failures=\"{}\" \
+                       time=\"{:.6}\">",
+                      escape_xml(pattern.name().unwrap_or(&uuid)),
+                      uuid,
+                      pattern.cases().len(),
+                      pattern.failures(),
+                      seconds(pattern.duration())));
+        for case in pattern.cases() {
+            try!(write!(out,
+                        "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.6}\"",
+                        uuid,
+                        escape_xml(case.message()),
+                        seconds(case.duration())));
+            match case.error() {
+                Some(error) => {
+                    let error = escape_xml(&error.to_string());
+                    try!(writeln!(out, ">"));
+                    try!(writeln!(out, "      <failure message=\"{}\">{}</failure>", error, error));
+                    try!(writeln!(out, "    </testcase>"));
+                }
+                None => try!(writeln!(out, "/>")),
+            }
+        }
+        try!(writeln!(out, "  </testsuite>"));
+    }
+    if !report.errors().is_empty() {
+        try!(writeln!(out,
+                      "  <testsuite name=\"errors\" tests=\"{}\" errors=\"{}\">",
+                      report.errors().len(),
+                      report.errors().len()));
+        for (index, error) in report.errors().iter().enumerate() {
+            let error = escape_xml(&error.to_string());
+            try!(writeln!(out, "    <testcase classname=\"errors\" name=\"{}\">", index));
+            try!(writeln!(out, "      <error message=\"{}\">{}</error>", error, error));
+            try!(writeln!(out, "    </testcase>"));
+        }
+        try!(writeln!(out, "  </testsuite>"));
+    }
+    writeln!(out, "</testsuites>")
+}
+
+pub fn to_value(report: &TestReport) -> Value {
+    let patterns = report.patterns().iter().map(pattern_to_value).collect();
+    let errors = report.errors().iter().map(|error| Value::String(error.to_string())).collect();
+
+    let mut object = BTreeMap::new();
+    object.insert("tests".to_owned(), Value::U64(report.tests() as u64));
+    object.insert("failures".to_owned(), Value::U64(report.failures() as u64));
+    object.insert("time".to_owned(), Value::F64(seconds(report.duration())));
+    object.insert("patterns".to_owned(), Value::Array(patterns));
+    object.insert("errors".to_owned(), Value::Array(errors));
+    Value::Object(object)
+}
+
+fn pattern_to_value(pattern: &PatternReport) -> Value {
+    let mut object = BTreeMap::new();
+    object.insert("uuid".to_owned(),
+                  Value::String(pattern.uuid().hyphenated().to_string()));
+    if let Some(name) = pattern.name() {
+        object.insert("name".to_owned(), Value::String(name.to_owned()));
+    }
+    object.insert("tests".to_owned(), Value::U64(pattern.cases().len() as u64));
+    object.insert("failures".to_owned(), Value::U64(pattern.failures() as u64));
+    object.insert("time".to_owned(), Value::F64(seconds(pattern.duration())));
+    let cases = pattern.cases().iter().map(case_to_value).collect();
+    object.insert("test_messages".to_owned(), Value::Array(cases));
+    Value::Object(object)
+}
+
+fn case_to_value(case: &TestCase) -> Value {
+    let mut object = BTreeMap::new();
+    object.insert("message".to_owned(), Value::String(case.message().to_owned()));
+    object.insert("expect".to_owned(),
+                  Value::String(case.expectation().name().to_owned()));
+    object.insert("passed".to_owned(), Value::Bool(case.passed()));
+    object.insert("time".to_owned(), Value::F64(seconds(case.duration())));
+    if let Some(error) = case.error() {
+        object.insert("error".to_owned(), Value::String(error.to_string()));
+    }
+    Value::Object(object)
+}
+
+pub fn write_json<W: Write>(report: &TestReport, out: &mut W) -> io::Result<()> {
+    let json = try!(serde_json::to_string_pretty(&to_value(report))
+                        .map_err(|error| io::Error::new(io::ErrorKind::Other, error)));
+    writeln!(out, "{}", json)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TestRunner, write_junit, write_json};
+    use matcher::pattern::file::PatternFile;
+    use matcher::trie::factory::TrieMatcherFactory;
+    use matcher::dump::Dump;
+
+    use serde_json;
+
+    const FILE: &'static str = r#"
+{
+  "patterns": [
+    {
+      "uuid": "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2",
+      "pattern": "user <%{GREEDY:user}> logged in"
+    },
+    {
+      "name": "LOGIN",
+      "uuid": "9a49c47d-29e9-4072-be84-3b76c6814743",
+      "pattern": "user %{INT:uid} logged in",
+      "test_messages": [
+        {"message": "user 1 logged in", "values": {"uid": "1"}},
+        {"message": "user 2 logged in", "values": {"uid": "3"}},
+        {"message": "user <x> logged in", "expect": "no_match_any"}
+      ]
+    }
+  ]
+}
+"#;
+
+    #[test]
+    fn test_given_pattern_file_when_its_test_messages_are_run_then_every_result_is_recorded() {
+        let file = serde_json::from_str::<PatternFile>(FILE).unwrap();
+        let (matcher, report) = TestRunner::run::<TrieMatcherFactory>(&mut file.into_iter());
+        assert!(matcher.parse("user 1 logged in").is_some());
+        assert_eq!(report.tests(), 3);
+        assert_eq!(report.failures(), 2);
+        assert!(!report.passed());
+
+        let login = &report.patterns()[0];
+        assert_eq!(login.name(), Some("LOGIN"));
+        assert_eq!(login.cases().len(), 3);
+        assert!(login.cases()[0].passed());
+        assert!(login.cases()[1].error().unwrap().to_string().contains("expected=3 got=2"));
+        assert_eq!(login.cases()[2].message(), "user <x> logged in");
+        assert!(!login.cases()[2].passed());
+
+        let mut junit = Vec::new();
+        write_junit(&report, &mut junit).unwrap();
+        let junit = String::from_utf8(junit).unwrap();
+        assert!(junit.contains("<testsuites tests=\"3\" failures=\"2\" errors=\"0\""));
+        assert!(junit.contains("<testsuite name=\"LOGIN\" id=\"9a49c47d-29e9-4072-be84-3b76c6814743\" \
+                                tests=\"3\" failures=\"2\""));
+        assert!(junit.contains("name=\"user &lt;x&gt; logged in\""));
+        assert!(junit.contains("<failure message=\"A parsed value does not equal"));
+
+        let mut json = Vec::new();
+        write_json(&report, &mut json).unwrap();
+        let json = serde_json::from_slice::<serde_json::Value>(&json).unwrap();
+        assert_eq!(json.lookup("failures").and_then(|failures| failures.as_u64()), Some(2));
+    }
+
+    #[test]
+    fn test_given_pattern_file_when_its_test_messages_are_run_then_the_coverage_can_be_reported
+        () {
+        let file = serde_json::from_str::<PatternFile>(FILE).unwrap();
+        let (matcher, report) = TestRunner::run::<TrieMatcherFactory>(&mut file.into_iter());
+        let coverage = report.coverage(&matcher.dump());
+        assert_eq!(report.patterns()[0].cases()[0].matched().map(|uuid| uuid.to_string()),
+                   Some("9a49c47d-29e9-4072-be84-3b76c6814743".to_owned()));
+        assert!(coverage.uncovered_patterns().is_empty());
+        assert_eq!(coverage.untested_patterns().len(), 1);
+        assert_eq!(coverage.untested_patterns()[0].to_string(),
+                   "fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2");
+        // it's covered by the failing test message of the other pattern
+        assert!(coverage.uncovered_branches().is_empty());
+        assert_eq!(coverage.ratio(), 1.0);
+    }
+}
@@ -1,12 +1,19 @@
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
 
-use parsers::ParseResult;
+use serde;
+use serde::ser::Error;
+
+use parsers::{ParseResult, ValueType, TypedValue, ConversionError};
 use matcher::Pattern;
 
 #[derive(Debug)]
 pub struct MatchResult<'a, 'b> {
     pattern: &'a Pattern,
     values: BTreeMap<&'a str, &'b str>,
+    types: BTreeMap<&'a str, ValueType>,
+    dynamic_values: BTreeMap<String, String>,
+    literal_len: usize,
 }
 
 impl <'a, 'b> MatchResult<'a, 'b> {
@@ -14,15 +21,46 @@ impl <'a, 'b> MatchResult<'a, 'b> {
         MatchResult {
             pattern: pattern,
             values: BTreeMap::new(),
+            types: BTreeMap::new(),
+            dynamic_values: BTreeMap::new(),
+            literal_len: 0,
         }
     }
 
     pub fn insert(&mut self, result: ParseResult<'a, 'b>) {
         if let Some(name) = result.parser().name() {
             self.values.insert(name, result.value());
+            self.types.insert(name, result.parser().value_type());
+        }
+        for (key, value) in result.into_values() {
+            self.insert_value(key, value);
         }
     }
 
+    pub fn insert_value(&mut self, key: String, value: String) {
+        self.dynamic_values.insert(key, value);
+    }
+
+    // the number of characters matched by literals
+    pub fn literal_len(&self) -> usize {
+        self.literal_len
+    }
+
+    pub fn add_literal_len(&mut self, len: usize) {
+        self.literal_len += len;
+    }
+
+    // The result with the higher priority is the greater one. If the priorities are
+    // equal, the one which matched more literal characters is the greater one, then
+    // the one with the lower UUID.
+    pub fn cmp_priority(&self, other: &MatchResult) -> Ordering {
+        self.pattern
+            .priority()
+            .cmp(&other.pattern.priority())
+            .then(self.literal_len.cmp(&other.literal_len))
+            .then(other.pattern.uuid().cmp(self.pattern.uuid()))
+    }
+
     pub fn pattern(&self) -> &Pattern {
         self.pattern
     }
@@ -30,14 +68,69 @@ impl <'a, 'b> MatchResult<'a, 'b> {
     pub fn values(&self) -> &BTreeMap<&'a str, &'b str> {
         &self.values
     }
+
+    // values which were emitted by the parsers, like the pairs of a KV parser
+    pub fn dynamic_values(&self) -> &BTreeMap<String, String> {
+        &self.dynamic_values
+    }
+
+    // the values emitted by the parsers are always strings
+    pub fn value_type(&self, key: &str) -> Option<ValueType> {
+        if let Some(value_type) = self.types.get(key) {
+            Some(*value_type)
+        } else if self.dynamic_values.contains_key(key) {
+            Some(ValueType::String)
+        } else {
+            None
+        }
+    }
+
+    // returns an error if the value cannot be converted to its parser's type
+    pub fn typed_value(&self, key: &str) -> Option<Result<TypedValue, ConversionError>> {
+        if let Some(value) = self.values.get(key) {
+            let value_type = self.types.get(key).cloned().unwrap_or(ValueType::String);
+            Some(MatchResult::convert(value_type, value))
+        } else {
+            self.dynamic_values.get(key).map(|value| Ok(TypedValue::String(value.clone())))
+        }
+    }
+
+    pub fn typed_values(&self) -> Result<BTreeMap<String, TypedValue>, ConversionError> {
+        let mut typed_values = BTreeMap::new();
+        for (key, value) in &self.dynamic_values {
+            typed_values.insert(key.clone(), TypedValue::String(value.clone()));
+        }
+        for (key, value) in &self.values {
+            let value_type = self.types.get(key).cloned().unwrap_or(ValueType::String);
+            typed_values.insert((*key).to_owned(), try!(MatchResult::convert(value_type, value)));
+        }
+        Ok(typed_values)
+    }
+
+    fn convert(value_type: ValueType, value: &str) -> Result<TypedValue, ConversionError> {
+        value_type.convert(value).ok_or_else(|| ConversionError::new(value_type, value))
+    }
+}
+
+// the values are serialized as a map with typed values
+impl<'a, 'b> serde::Serialize for MatchResult<'a, 'b> {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        match self.typed_values() {
+            Ok(typed_values) => typed_values.serialize(serializer),
+            Err(err) => Err(S::Error::custom(err.to_string())),
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use parsers::{IntParser, ParseResult};
+    use parsers::{IntParser, SetParser, ParseResult, TypedValue, ValueType, ConversionError};
     use super::MatchResult;
     use matcher::Pattern;
     use std::collections::BTreeMap;
+    use serde_json;
 
     #[test]
     fn test_given_match_result_when_a_parse_result_is_inserted_then_we_use_only_the_ones_where_the_parser_has_a_name
@@ -56,4 +149,46 @@ mod test {
         match_result.insert(ParseResult::new(&parser_with_name, "c"));
         assert_eq!(match_result.values(), &expected_values);
     }
+
+    #[test]
+    fn test_given_match_result_when_a_parse_result_with_emitted_values_is_inserted_then_they_are_stored_separately
+        () {
+        let parser = IntParser::with_name("name".to_owned());
+        let pattern = Pattern::with_random_uuid();
+        let mut match_result = MatchResult::new(&pattern);
+        let mut parse_result = ParseResult::new(&parser, "c");
+        parse_result.push_value("key".to_owned(), "value".to_owned());
+        match_result.insert(parse_result);
+        assert_eq!(match_result.values().get("name"), Some(&"c"));
+        assert_eq!(match_result.dynamic_values().get("key").map(|x| &x[..]), Some("value"));
+    }
+
+    #[test]
+    fn test_given_match_result_when_it_contains_typed_values_then_they_are_serialized_with_their_types
+        () {
+        let int_parser = IntParser::with_name("pid".to_owned());
+        let set_parser = SetParser::with_name("app".to_owned(), "0123456789abc");
+        let pattern = Pattern::with_random_uuid();
+        let mut match_result = MatchResult::new(&pattern);
+        match_result.insert(ParseResult::new(&int_parser, "123"));
+        match_result.insert(ParseResult::new(&set_parser, "42"));
+        assert_eq!(match_result.value_type("pid"), Some(ValueType::Int));
+        assert_eq!(match_result.value_type("app"), Some(ValueType::String));
+        assert_eq!(match_result.typed_value("pid"), Some(Ok(TypedValue::Int(123))));
+        assert_eq!(match_result.typed_value("missing"), None);
+        assert_eq!(serde_json::to_string(&match_result).unwrap(),
+                   r#"{"app":"42","pid":123}"#);
+    }
+
+    #[test]
+    fn test_given_match_result_when_a_value_cannot_be_converted_to_its_type_then_we_get_an_error() {
+        let int_parser = IntParser::with_name("pid".to_owned());
+        let pattern = Pattern::with_random_uuid();
+        let mut match_result = MatchResult::new(&pattern);
+        match_result.insert(ParseResult::new(&int_parser, "99999999999999999999"));
+        let err = ConversionError::new(ValueType::Int, "99999999999999999999");
+        assert_eq!(match_result.typed_value("pid"), Some(Err(err.clone())));
+        assert_eq!(match_result.typed_values(), Err(err));
+        assert_eq!(serde_json::to_string(&match_result).is_err(), true);
+    }
 }
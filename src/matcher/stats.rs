@@ -0,0 +1,119 @@
+use std::collections::BTreeMap;
+
+// Structural statistics of a matcher. The matchers walk their nodes and report
+// them with the visit_*() methods.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatcherStats {
+    patterns: usize,
+    literal_nodes: usize,
+    parser_nodes: usize,
+    max_depth: usize,
+    inner_nodes: usize,
+    // the number of parser children -> the number of nodes which have so many
+    parser_siblings: BTreeMap<usize, usize>,
+    min_memory: usize,
+}
+
+impl MatcherStats {
+    pub fn new() -> MatcherStats {
+        MatcherStats::default()
+    }
+
+    // a node with its literal and parser children, the depth of the root is 0,
+    // the memory arguments are lower bounds like min_memory()
+    pub fn visit_node(&mut self,
+                      depth: usize,
+                      literal_children: usize,
+                      parser_children: usize,
+                      memory: usize) {
+        if literal_children + parser_children > 0 {
+            self.inner_nodes += 1;
+            self.max_depth = ::std::cmp::max(self.max_depth, depth + 1);
+        }
+        if parser_children > 0 {
+            *self.parser_siblings.entry(parser_children).or_insert(0) += 1;
+        }
+        self.min_memory += memory;
+    }
+
+    pub fn visit_literal(&mut self, memory: usize) {
+        self.literal_nodes += 1;
+        self.min_memory += memory;
+    }
+
+    pub fn visit_parser(&mut self, memory: usize) {
+        self.parser_nodes += 1;
+        self.min_memory += memory;
+    }
+
+    pub fn visit_pattern(&mut self, memory: usize) {
+        self.patterns += 1;
+        self.min_memory += memory;
+    }
+
+    pub fn patterns(&self) -> usize {
+        self.patterns
+    }
+
+    pub fn literal_nodes(&self) -> usize {
+        self.literal_nodes
+    }
+
+    pub fn parser_nodes(&self) -> usize {
+        self.parser_nodes
+    }
+
+    // the number of edges on the longest path from the root
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    // the average number of children of the nodes which have children
+    pub fn average_branching_factor(&self) -> f64 {
+        if self.inner_nodes == 0 {
+            0.0
+        } else {
+            (self.literal_nodes + self.parser_nodes) as f64 / self.inner_nodes as f64
+        }
+    }
+
+    // The parser children of a node are tried one after another, so the nodes with
+    // many parser siblings are the hot spots of the matching.
+    pub fn parser_siblings(&self) -> &BTreeMap<usize, usize> {
+        &self.parser_siblings
+    }
+
+    pub fn max_parser_siblings(&self) -> usize {
+        self.parser_siblings.keys().next_back().cloned().unwrap_or(0)
+    }
+
+    // A lower bound of the memory used by the matcher in bytes. The matchers count
+    // the size of their nodes, literals and patterns, but not what the parsers and
+    // the tokens of the patterns allocate on the heap.
+    pub fn min_memory(&self) -> usize {
+        self.min_memory
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MatcherStats;
+
+    #[test]
+    fn test_given_stats_when_nodes_are_visited_then_the_branching_factor_and_the_depth_are_calculated
+        () {
+        let mut stats = MatcherStats::new();
+        stats.visit_node(0, 1, 2, 10);
+        stats.visit_literal(5);
+        stats.visit_parser(5);
+        stats.visit_parser(5);
+        stats.visit_node(1, 1, 0, 10);
+        stats.visit_literal(5);
+        stats.visit_pattern(20);
+        assert_eq!(stats.max_depth(), 2);
+        assert_eq!(stats.average_branching_factor(), 2.0);
+        assert_eq!(stats.max_parser_siblings(), 2);
+        assert_eq!(stats.parser_siblings().get(&2), Some(&1));
+        assert_eq!(stats.min_memory(), 60);
+    }
+}
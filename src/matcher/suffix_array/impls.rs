@@ -12,14 +12,19 @@ use matcher::{
 };
 use matcher::compiled_pattern::TokenType;
 use matcher::result::MatchResult;
+use matcher::dump::{Dump, DumpNode};
+use matcher::stats::MatcherStats;
 use utils::CommonPrefix;
 
 use std::borrow::Borrow;
+use std::mem;
 
 #[derive(Debug, Clone)]
 pub struct SuffixTable {
     literal_entries: Vec<LiteralE>,
     parser_entries: Vec<ParserE>,
+    // it's true if a pattern with a non-default priority was inserted
+    prioritized: bool,
 }
 
 impl SuffixTable {
@@ -82,6 +87,50 @@ impl SuffixTable {
         }
     }
 
+    // Returns the literal entries which are prefixes of the value, the longest first.
+    // They start with the same character as the value, so they are next to each
+    // other and they precede the position of the value.
+    fn prefix_entries<'a, 'b>(&'a self, value: &'b str) -> Vec<&'a LiteralE> {
+        let end = match self.literal_entries.binary_search_by(|probe| probe.literal()[..].cmp(value)) {
+            Ok(pos) => pos + 1,
+            Err(pos) => pos,
+        };
+        let first_char = value.chars().next();
+        self.literal_entries[..end]
+            .iter()
+            .rev()
+            .take_while(|entry| entry.literal().chars().next() == first_char)
+            .filter(|entry| value.starts_with(&entry.literal()[..]))
+            .collect()
+    }
+
+    // the parser entries are tried if the value cannot be matched by a literal entry
+    pub fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
+        for child in self.prefix_entries(value) {
+            if let Some(result) = child.parse(value) {
+                return Some(result);
+            }
+        }
+        self.parse_with_parsers(value)
+    }
+
+    // It returns every complete match in the same order as parse() tries them.
+    pub fn parse_all<'a, 'b>(&'a self, value: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        let mut results = Vec::new();
+        for child in self.prefix_entries(value) {
+            results.extend(child.parse_all(value));
+        }
+        for parser in &self.parser_entries {
+            results.extend(parser.parse_all(value));
+        }
+        results
+    }
+
+    // The match with the highest priority is returned, so every alternative is tried.
+    pub fn parse_prioritized<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
+        self.parse_all(value).into_iter().max_by(|a, b| a.cmp_priority(b))
+    }
+
     pub fn longest_common_prefix<'a, 'b>(&'a self, value: &'b str) -> Option<&'a LiteralE> {
         let result = self.literal_entries.binary_search_by(|probe| {
             let s: &str = probe.literal().borrow();
@@ -94,11 +143,68 @@ impl SuffixTable {
     }
 }
 
+impl Dump for SuffixTable {
+    fn dump(&self) -> DumpNode {
+        let mut root = DumpNode::root();
+        self.dump_children(&mut root);
+        root
+    }
+}
+
+impl SuffixTable {
+    fn dump_children(&self, parent: &mut DumpNode) {
+        for entry in &self.literal_entries {
+            let mut node = DumpNode::literal(entry.literal(),
+                                             entry.pattern().map(|pattern| *pattern.uuid()));
+            if let Some(table) = entry.child() {
+                table.dump_children(&mut node);
+            }
+            parent.push_child(node);
+        }
+        for entry in &self.parser_entries {
+            let mut node = DumpNode::parser(&*entry.parser,
+                                            entry.pattern().map(|pattern| *pattern.uuid()));
+            if let Some(table) = entry.child() {
+                table.dump_children(&mut node);
+            }
+            parent.push_child(node);
+        }
+    }
+}
+
+impl SuffixTable {
+    fn collect_stats(&self, depth: usize, stats: &mut MatcherStats) {
+        stats.visit_node(depth,
+                         self.literal_entries.len(),
+                         self.parser_entries.len(),
+                         mem::size_of::<SuffixTable>());
+        for entry in &self.literal_entries {
+            stats.visit_literal(mem::size_of::<LiteralE>() + entry.literal().len());
+            if let Some(pattern) = entry.pattern() {
+                stats.visit_pattern(pattern.min_memory());
+            }
+            if let Some(table) = entry.child() {
+                table.collect_stats(depth + 1, stats);
+            }
+        }
+        for entry in &self.parser_entries {
+            stats.visit_parser(mem::size_of::<ParserE>() + mem::size_of_val(&*entry.parser));
+            if let Some(pattern) = entry.pattern() {
+                stats.visit_pattern(pattern.min_memory());
+            }
+            if let Some(table) = entry.child() {
+                table.collect_stats(depth + 1, stats);
+            }
+        }
+    }
+}
+
 impl Default for SuffixTable {
     fn default() -> Self {
         SuffixTable {
             literal_entries: Vec::new(),
-            parser_entries: Vec::new()
+            parser_entries: Vec::new(),
+            prioritized: false
         }
     }
 }
@@ -109,6 +215,9 @@ impl SuffixArray for SuffixTable {
     }
 
     fn insert(&mut self, mut pattern: Pattern) {
+        if pattern.priority() != 0 {
+            self.prioritized = true;
+        }
         if let Some(token) = pattern.pop_first_token() {
             let mut entry: &mut Entry<SA=SuffixTable> = match token {
                 TokenType::Literal(literal) => {
@@ -184,22 +293,49 @@ impl ParserEntry for ParserE {
         &self.parser
     }
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
-        self.parser.parse(value).and_then(|kvpair| {
-            let value = value.ltrunc(kvpair.value().len());
+        let mut next_kvpair = self.parser.parse(value);
 
-            if let Some(child) = self.child() {
-                child.parse(value).and_then(|mut result| {
+        while let Some(kvpair) = next_kvpair {
+            let parsed_len = kvpair.match_len();
+            let rest = value.ltrunc(parsed_len);
+
+            let result = if rest.is_empty() && self.pattern.is_some() {
+                self.create_match_result(kvpair)
+            } else if let Some(child) = self.child() {
+                child.parse(rest).and_then(|mut result| {
                     result.insert(kvpair);
                     Some(result)
                 })
             } else {
-                if value.is_empty() {
-                    self.create_match_result(kvpair)
-                } else {
-                    None
+                None
+            };
+            if result.is_some() {
+                return result;
+            }
+            next_kvpair = self.parser.parse_next(value, parsed_len);
+        }
+        None
+    }
+    fn parse_all<'a, 'b>(&'a self, value: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        let mut results = Vec::new();
+        let mut next_kvpair = self.parser.parse(value);
+
+        while let Some(kvpair) = next_kvpair {
+            let parsed_len = kvpair.match_len();
+            let rest = value.ltrunc(parsed_len);
+
+            if rest.is_empty() && self.pattern.is_some() {
+                results.extend(self.create_match_result(kvpair.clone()));
+            }
+            if let Some(child) = self.child() {
+                for mut result in child.parse_all(rest) {
+                    result.insert(kvpair.clone());
+                    results.push(result);
                 }
             }
-        })
+            next_kvpair = self.parser.parse_next(value, parsed_len);
+        }
+        results
     }
 }
 
@@ -239,6 +375,34 @@ impl Entry for LiteralE {
     }
 }
 
+impl LiteralE {
+    // the value has to start with the literal
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
+        let result = if self.literal.len() == value.len() {
+            self.pattern().map(|pattern| MatchResult::new(pattern))
+        } else {
+            self.child().and_then(|child| child.parse(value.ltrunc(self.literal.len())))
+        };
+        result.map(|mut result| {
+            result.add_literal_len(self.literal.len());
+            result
+        })
+    }
+
+    fn parse_all<'a, 'b>(&'a self, value: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        let mut results = Vec::new();
+        if self.literal.len() == value.len() {
+            results.extend(self.pattern().map(|pattern| MatchResult::new(pattern)));
+        } else if let Some(child) = self.child() {
+            results = child.parse_all(value.ltrunc(self.literal.len()));
+        }
+        for result in &mut results {
+            result.add_literal_len(self.literal.len());
+        }
+        results
+    }
+}
+
 impl LiteralEntry for LiteralE {
     fn literal(&self) -> &String {
         &self.literal
@@ -247,21 +411,22 @@ impl LiteralEntry for LiteralE {
 
 impl Matcher for SuffixTable {
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
-        if let Some(child) = self.longest_common_prefix(value) {
-            let common_prefix_len = child.literal().common_prefix_len(value);
-            if common_prefix_len == value.len() {
-                child.pattern().and_then(|pattern| Some(MatchResult::new(pattern)))
-            } else if common_prefix_len < value.len() {
-                let value = value.ltrunc(common_prefix_len);
-                child.child().and_then(|child| child.parse(value))
-            } else {
-                None
-            }
+        if self.prioritized {
+            self.parse_prioritized(value)
         } else {
-            self.parse_with_parsers(value)
+            self.parse(value)
         }
     }
-    fn add_pattern(&mut self, pattern: Pattern) {
+    fn parse_all<'a, 'b>(&'a self, value: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        self.parse_all(value)
+    }
+    fn stats(&self) -> MatcherStats {
+        let mut stats = MatcherStats::new();
+        self.collect_stats(0, &mut stats);
+        stats
+    }
+    fn add_pattern(&mut self, mut pattern: Pattern) {
+        pattern.apply_literal_options();
         self.insert(pattern);
     }
     fn boxed_clone(&self) -> Box<Matcher> {
@@ -18,8 +18,9 @@ pub trait Entry {
         if pattern.pattern().is_empty() {
             self.set_pattern(Some(pattern));
         } else {
-            let sa = Self::SA::new();
-            self.set_child(Some(sa));
+            if self.child().is_none() {
+                self.set_child(Some(Self::SA::new()));
+            }
             self.child_mut().expect("Failed to get a child").insert(pattern);
         }
     }
@@ -31,5 +32,6 @@ pub trait LiteralEntry: Entry + Clone {
 
 pub trait ParserEntry: Entry + Clone {
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>>;
+    fn parse_all<'a, 'b>(&'a self, value: &'b str) -> Vec<MatchResult<'a, 'b>>;
     fn parser(&self) -> &Box<Parser>;
 }
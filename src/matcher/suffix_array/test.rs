@@ -161,3 +161,208 @@ fn test_given_parser_when_it_receives_utf_8_strings_then_it_does_not_panic() {
 
     assert_eq!(true, root.parse("micek ¡micek").is_some());
 }
+
+#[test]
+fn test_given_successive_greedy_parser_when_the_first_end_string_is_not_the_right_one_then_the_next_one_is_tried() {
+    let compiled_pattern = ::grammar::parser::pattern(r#"from %{GREEDY(mode="successive"):from}: %{INT:n}"#)
+                  .expect("Failed to compile a pattern with a successive GREEDY parser");
+
+    let mut pattern = Pattern::with_random_uuid();
+    pattern.set_pattern(compiled_pattern);
+
+    let mut root = SuffixTable::new();
+    root.insert(pattern);
+
+    let result = root.parse("from a:b: c: 123").expect("Failed to parse with a successive GREEDY parser");
+    assert_eq!(result.values().get("from"), Some(&"a:b: c"));
+}
+
+#[test]
+fn test_given_estring_parser_when_it_consumes_its_delimiter_then_the_next_token_starts_after_it() {
+    let mut root = SuffixTable::new();
+    for input in &[r#"user=%{ESTRING(" "):user}%{INT:uid}"#,
+                   r#"group=%{ESTRING(",", consume="false"):group},%{INT:gid}"#] {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+        root.insert(pattern);
+    }
+
+    let result = root.parse("user=root 0").expect("Failed to parse with an ESTRING parser");
+    assert_eq!(result.values().get("user"), Some(&"root"));
+    assert_eq!(result.values().get("uid"), Some(&"0"));
+    let result = root.parse("group=wheel,10").expect("Failed to parse with an ESTRING parser");
+    assert_eq!(result.values().get("group"), Some(&"wheel"));
+    assert_eq!(result.values().get("gid"), Some(&"10"));
+}
+
+#[test]
+fn test_given_suffix_array_when_the_child_of_a_literal_entry_does_not_match_then_the_parser_entries_are_tried() {
+    let mut root = SuffixTable::new();
+    for input in &["user %{INT:uid} logged in",
+                   "%{SET(\"abcdefghijklmnopqrstuvwxyz\"):name} %{INT:uid} logged out"] {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+        root.insert(pattern);
+    }
+
+    let result = root.parse("user 0 logged in").expect("Failed to parse with a literal entry");
+    assert_eq!(result.values().get("uid"), Some(&"0"));
+    let result = root.parse("user 0 logged out").expect("Failed to parse with a parser entry");
+    assert_eq!(result.values().get("name"), Some(&"user"));
+    assert_eq!(result.values().get("uid"), Some(&"0"));
+    assert_eq!(root.parse("user 0 logged").is_none(), true);
+}
+
+#[test]
+fn test_given_suffix_array_when_the_longest_prefix_entry_does_not_match_then_the_shorter_ones_are_tried() {
+    let mut root = SuffixTable::new();
+    for input in &["user root logged %{INT:uid}",
+                   "user %{GREEDY:user} logged out"] {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+        root.insert(pattern);
+    }
+
+    let result = root.parse("user root logged 0").expect("Failed to parse with the longest literal entry");
+    assert_eq!(result.values().get("uid"), Some(&"0"));
+    let result = root.parse("user root logged out").expect("Failed to parse with a shorter literal entry");
+    assert_eq!(result.values().get("user"), Some(&"root"));
+    assert_eq!(root.parse("user root logged in").is_none(), true);
+}
+
+#[test]
+fn test_given_suffix_array_when_patterns_with_a_common_parser_prefix_are_inserted_then_none_of_them_is_lost() {
+    let mut root = SuffixTable::new();
+    for input in &["foo %{INT:a} x", "foo %{INT:a} y", "foo %{INT:a}"] {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+        root.insert(pattern);
+    }
+
+    assert_eq!(root.parse("foo 1 x").is_some(), true);
+    assert_eq!(root.parse("foo 1 y").is_some(), true);
+    assert_eq!(root.parse("foo 1").is_some(), true);
+}
+
+#[test]
+fn test_given_suffix_array_when_a_literal_entry_has_common_prefix_with_the_value_but_does_not_match_then_the_parsers_are_tried() {
+    let mut root = SuffixTable::new();
+    for input in &["ERROR: disk %{INT:disk}",
+                   "%{ICASE('error: ')}net %{INT:net}"] {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+        root.insert(pattern);
+    }
+
+    let result = root.parse("ERROR: disk 1").expect("Failed to parse with a literal entry");
+    assert_eq!(result.values().get("disk"), Some(&"1"));
+    let result = root.parse("ERROR: net 2").expect("Failed to parse with a case-insensitive literal");
+    assert_eq!(result.values().get("net"), Some(&"2"));
+    assert_eq!(root.parse("ERROR: dis").is_none(), true);
+}
+
+#[test]
+fn test_given_whitespace_tolerant_pattern_when_the_whitespace_runs_of_the_message_differ_then_it_still_matches
+    () {
+    let mut root = SuffixTable::new();
+    for (input, tolerant) in vec![("user  %{INT:uid}  logged in", true),
+                                  ("user %{INT:uid} logged out", false),
+                                  ("Accepted password for %{GREEDY:user} from", false),
+                                  ("Accepted  publickey for  %{INT:uid}", true)] {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+        pattern.set_whitespace_tolerant(tolerant);
+        root.add_pattern(pattern);
+    }
+
+    let result = root.parse("user\t0 logged   in").expect("Failed to parse with a whitespace tolerant pattern");
+    assert_eq!(result.values().get("uid"), Some(&"0"));
+    assert_eq!(root.parse("user 0 logged out").is_some(), true);
+    assert_eq!(root.parse("user 0 logged  out").is_none(), true);
+    assert_eq!(root.parse("user 0 loggedin").is_none(), true);
+    let result = root.parse("Accepted publickey\tfor   0").expect("Failed to parse with a whitespace tolerant pattern");
+    assert_eq!(result.values().get("uid"), Some(&"0"));
+}
+
+#[test]
+fn test_given_suffix_array_when_more_patterns_match_then_the_one_with_the_highest_priority_wins() {
+    let mut root = SuffixTable::new();
+    for &(input, priority) in &[("user %{GREEDY:user} logged in", 1),
+                                ("user %{INT:uid} logged in", 0),
+                                ("user %{INT:uid} logged out", 0),
+                                ("user %{GREEDY:user} logged out", -1),
+                                ("user root logged out", 0)] {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+        pattern.set_priority(priority);
+        root.insert(pattern);
+    }
+
+    let result = Matcher::parse(&root, "user 0 logged in").expect("Failed to parse a prioritized message");
+    assert_eq!(result.pattern().priority(), 1);
+    assert_eq!(result.values().get("user"), Some(&"0"));
+    let result = Matcher::parse(&root, "user 0 logged out").expect("Failed to parse a prioritized message");
+    assert_eq!(result.values().get("uid"), Some(&"0"));
+    let result = Matcher::parse(&root, "user root logged out").expect("Failed to parse a prioritized message");
+    assert_eq!(result.literal_len(), "user root logged out".len());
+}
+
+#[test]
+fn test_given_suffix_array_when_more_patterns_match_then_parse_all_returns_each_of_them() {
+    let mut root = SuffixTable::new();
+    for input in &["user %{GREEDY:user} logged in",
+                   "user %{INT:uid} logged in",
+                   "user %{INT:uid} logged out",
+                   "user root logged in"] {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+        root.insert(pattern);
+    }
+
+    assert_eq!(Matcher::parse_all(&root, "user 0 logged in").len(), 2);
+    assert_eq!(Matcher::parse_all(&root, "user root logged in").len(), 2);
+    assert_eq!(Matcher::parse_all(&root, "user root logged out").is_empty(), true);
+    let results = Matcher::parse_all(&root, "user 0 logged out");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].values().get("uid"), Some(&"0"));
+}
+
+#[test]
+fn test_given_suffix_array_when_it_is_dumped_as_dot_then_every_entry_is_an_edge() {
+    use matcher::dump::{Dump, write_dot};
+
+    let mut root = SuffixTable::new();
+    for input in &["foo %{INT:a} x", "foo %{INT:a}", "fox"] {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+        root.insert(pattern);
+    }
+
+    let dump = root.dump();
+    assert_eq!(dump.counts(), (3, 1, 3));
+    let mut out = Vec::new();
+    write_dot(&dump, &mut out).unwrap();
+    let dot = String::from_utf8(out).unwrap();
+    assert!(dot.starts_with("digraph matcher {\n  label=\"literal nodes: 3, parser nodes: 1, patterns: 3\";\n"));
+    assert!(dot.contains("[label=\"%{INT:a}\", style=dashed];"));
+    assert!(dot.contains("[label=\"\\\" x\\\"\"];"));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn test_given_suffix_array_when_its_stats_are_calculated_then_every_entry_is_counted() {
+    let mut root = SuffixTable::new();
+    for input in &["foo %{INT:a} x", "foo %{INT:a}", "fox", "foo %{GREEDY:b} y"] {
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+        root.insert(pattern);
+    }
+
+    let stats = root.stats();
+    assert_eq!(stats.patterns(), 4);
+    assert_eq!(stats.literal_nodes(), 4);
+    assert_eq!(stats.parser_nodes(), 2);
+    assert_eq!(stats.max_depth(), 3);
+    assert_eq!(stats.average_branching_factor(), 6.0 / 4.0);
+    assert_eq!(stats.parser_siblings().get(&2), Some(&1));
+}
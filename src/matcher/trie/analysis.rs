@@ -0,0 +1,132 @@
+use uuid::Uuid;
+
+use matcher::Matcher;
+use matcher::trie::SuffixTree;
+
+use std::fmt;
+
+// Two patterns overlap if they match the same message and only one of them wins,
+// for example when an INT parser precedes a SET parser with hexadecimal digits in
+// the same node of the trie, or when a GREEDY parser at the end of a path swallows
+// the rest of the message. The overlap is found on a single example message, so the
+// losing pattern can still win on other messages (the SET parser above still
+// matches "ff"), it isn't necessarily shadowed completely. This isn't a shadowing
+// analysis, the overlaps are hints for the pattern authors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Overlap {
+    overlapped: Uuid,
+    winner: Uuid,
+    message: String,
+}
+
+impl Overlap {
+    pub fn new(overlapped: Uuid, winner: Uuid, message: String) -> Overlap {
+        Overlap {
+            overlapped: overlapped,
+            winner: winner,
+            message: message,
+        }
+    }
+
+    // the pattern which loses on the example message
+    pub fn overlapped(&self) -> &Uuid {
+        &self.overlapped
+    }
+
+    pub fn winner(&self) -> &Uuid {
+        &self.winner
+    }
+
+    // an example message which is matched by both patterns
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Overlap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f,
+               "pattern {} overlaps with pattern {} which wins on the example message: {:?}",
+               self.overlapped.hyphenated(),
+               self.winner.hyphenated(),
+               self.message)
+    }
+}
+
+// Generates an example message for every pattern of the trie and reports the ones
+// where an other pattern wins. Only the trie is analysed, the other matchers pick
+// their winners differently. The examples which aren't matched by their own
+// patterns (for example when the example of a parser contains the following literal)
+// are skipped.
+pub fn find_overlaps(trie: &SuffixTree) -> Vec<Overlap> {
+    let mut overlaps = Vec::new();
+
+    for (pattern, example) in trie.examples() {
+        let is_matching = trie.parse_all(&example)
+                              .iter()
+                              .any(|result| result.pattern().uuid() == pattern.uuid());
+        if !is_matching {
+            debug!("The example message doesn't match its pattern: uuid={} message={:?}",
+                   pattern.uuid().hyphenated(),
+                   example);
+            continue;
+        }
+        if let Some(result) = Matcher::parse(trie, &example) {
+            if result.pattern().uuid() != pattern.uuid() {
+                overlaps.push(Overlap::new(*pattern.uuid(), *result.pattern().uuid(), example.clone()));
+            }
+        }
+    }
+    overlaps
+}
+
+#[cfg(test)]
+mod test {
+    use super::find_overlaps;
+    use matcher::trie::SuffixTree;
+    use matcher::trie::node::interface::SuffixTree as STree;
+    use matcher::pattern::Pattern;
+    use uuid::Uuid;
+
+    fn create_trie(patterns: &[(&str, &str)]) -> SuffixTree {
+        let mut trie = SuffixTree::new();
+        for &(uuid, input) in patterns {
+            let mut pattern = Pattern::with_uuid(Uuid::parse_str(uuid).unwrap());
+            pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+            trie.insert(pattern);
+        }
+        trie
+    }
+
+    #[test]
+    fn test_given_trie_when_an_int_parser_precedes_a_hexadecimal_set_parser_then_the_overlap_is_reported
+        () {
+        let int_uuid = "d3b5b9c8-5ec8-4d2d-a4f7-1a0e2f2b6c4a";
+        let hex_uuid = "9f0e8a51-0c55-4e41-9d8a-23d1a7a9f6f1";
+        let trie = create_trie(&[(int_uuid, "id: %{INT:id}"),
+                                 (hex_uuid, "id: %{SET(\"0123456789abcdef\"):id}")]);
+        let overlaps = find_overlaps(&trie);
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].overlapped().hyphenated().to_string(), hex_uuid);
+        assert_eq!(overlaps[0].winner().hyphenated().to_string(), int_uuid);
+        assert_eq!(overlaps[0].message(), "id: 0");
+        // the overlapped pattern still wins where the winner doesn't match
+        let result = ::matcher::Matcher::parse(&trie, "id: ff").unwrap();
+        assert_eq!(result.pattern().uuid().hyphenated().to_string(), hex_uuid);
+    }
+
+    #[test]
+    fn test_given_trie_when_a_greedy_parser_swallows_the_end_of_the_message_then_the_overlap_is_reported
+        () {
+        let greedy_uuid = "4b8f4d0e-0b6a-4f3e-8a5a-0f5a2d7c9e11";
+        let int_uuid = "7c1e3a2b-6d4f-4a9b-8c2d-5e6f7a8b9c0d";
+        let trie = create_trie(&[(greedy_uuid, "session opened for %{GREEDY:rest}"),
+                                 (int_uuid, "session opened for %{INT:uid} by %{INT:by}"),
+                                 ("e5c6d7f8-1a2b-4c3d-9e4f-5a6b7c8d9e0f", "session closed for %{INT:uid}")]);
+        let overlaps = find_overlaps(&trie);
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].overlapped().hyphenated().to_string(), int_uuid);
+        assert_eq!(overlaps[0].winner().hyphenated().to_string(), greedy_uuid);
+        assert_eq!(overlaps[0].message(), "session opened for 0 by 0");
+    }
+}
@@ -1,16 +1,30 @@
 use matcher::Matcher;
 use super::SuffixTree;
 use matcher::result::MatchResult;
+use matcher::stats::MatcherStats;
 use matcher::pattern::Pattern;
 use matcher::trie::node::interface::SuffixTree as STree;
 
 impl Matcher for SuffixTree {
     fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
-        self.parse(text)
+        if self.is_prioritized() {
+            self.parse_prioritized(text)
+        } else {
+            self.parse(text)
+        }
     }
-    fn add_pattern(&mut self, pattern: Pattern) {
+    fn parse_all<'a, 'b>(&'a self, text: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        self.parse_all(text)
+    }
+    fn add_pattern(&mut self, mut pattern: Pattern) {
+        pattern.apply_literal_options();
         self.insert(pattern);
     }
+    fn stats(&self) -> MatcherStats {
+        let mut stats = MatcherStats::new();
+        self.collect_stats(0, &mut stats);
+        stats
+    }
     fn boxed_clone(&self) -> Box<Matcher> {
         Box::new(self.clone())
     }
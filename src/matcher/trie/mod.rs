@@ -2,6 +2,7 @@ pub mod node;
 pub mod parser_factory;
 pub mod factory;
 pub mod suite;
+pub mod analysis;
 mod matcher;
 
 pub use self::suite::TrieMatcherSuite;
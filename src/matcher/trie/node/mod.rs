@@ -3,6 +3,10 @@ use utils::{SortedVec, CommonPrefix};
 use matcher::pattern::Pattern;
 use matcher::result::MatchResult;
 use matcher::compiled_pattern::TokenType;
+use matcher::dump::{Dump, DumpNode};
+use matcher::stats::MatcherStats;
+
+use std::mem;
 
 mod literal;
 mod parser;
@@ -17,6 +21,8 @@ pub use self::parser::ParserNode;
 pub struct SuffixTree {
     literal_children: SortedVec<LiteralNode>,
     parser_children: Vec<ParserNode>,
+    // it's true if a pattern with a non-default priority was inserted
+    prioritized: bool,
 }
 
 enum LiteralLookupResult<'a> {
@@ -158,29 +164,97 @@ impl SuffixTree {
         }
     }
 
+    // If the literal children can't be used to match the text, the parser children
+    // of the same node are tried. They can be parsers which match literals (like
+    // case-insensitive ones), so they are tried even if a literal child has a common
+    // prefix with the text.
     pub fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
         trace!("parse(): text = {}", text);
-        match self.lookup_literal(text) {
-            Ok((node, pos)) => {
-                trace!("{:?}", node);
-                let child = node.literal_children
+        let result = match self.search(text) {
+            LiteralLookupResult::Found(pos) => {
+                let child = self.literal_children
                                 .get(pos)
                                 .expect("Failed to get a looked up child");
-                SuffixTree::create_match_result_if_child_is_leaf(child)
+                SuffixTree::create_match_result_if_child_is_leaf(child, text.len())
             }
-            Err((node, remaining_len)) => {
-                let text = text.ltrunc(text.len() - remaining_len);
-                trace!("parse(): text = {}", text);
-                trace!("parse(): #parser_children = {}", node.parser_children.len());
-                node.parse_with_parsers(text)
+            LiteralLookupResult::GoDown(pos, truncated_text) => {
+                self.literal_children
+                    .get(pos)
+                    .and_then(|child| child.node())
+                    .and_then(|node| node.parse(truncated_text))
+                    .map(|mut result| {
+                        result.add_literal_len(text.len() - truncated_text.len());
+                        result
+                    })
             }
+            LiteralLookupResult::NotFound => None,
+        };
+        result.or_else(|| self.empty_literal_node(text).and_then(|node| node.parse(text)))
+              .or_else(|| {
+                  trace!("parse(): #parser_children = {}", self.parser_children.len());
+                  self.parse_with_parsers(text)
+              })
+    }
+
+    // It returns every complete match in the same order as parse() tries them.
+    pub fn parse_all<'a, 'b>(&'a self, text: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        let mut results = Vec::new();
+        match self.search(text) {
+            LiteralLookupResult::Found(pos) => {
+                let child = self.literal_children
+                                .get(pos)
+                                .expect("Failed to get a looked up child");
+                results.extend(SuffixTree::create_match_result_if_child_is_leaf(child, text.len()));
+            }
+            LiteralLookupResult::GoDown(pos, truncated_text) => {
+                if let Some(node) = self.literal_children.get(pos).and_then(|child| child.node()) {
+                    for mut result in node.parse_all(truncated_text) {
+                        result.add_literal_len(text.len() - truncated_text.len());
+                        results.push(result);
+                    }
+                }
+            }
+            LiteralLookupResult::NotFound => {}
+        }
+        if let Some(node) = self.empty_literal_node(text) {
+            results.extend(node.parse_all(text));
+        }
+        for parser in &self.parser_children {
+            results.extend(parser.parse_all(text));
         }
+        results
+    }
+
+    // The match with the highest priority is returned, so every alternative is tried.
+    pub fn parse_prioritized<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
+        self.parse_all(text).into_iter().max_by(|a, b| a.cmp_priority(b))
+    }
+
+    pub fn is_prioritized(&self) -> bool {
+        self.prioritized
+    }
+
+    // If a literal is the prefix of an other one, it's split and the shorter one continues
+    // in an empty literal child. It's always the first one, but the binary search
+    // doesn't find it.
+    fn empty_literal_node(&self, text: &str) -> Option<&SuffixTree> {
+        self.literal_children
+            .get(0)
+            .and_then(|child| {
+                if child.literal().is_empty() && !text.is_empty() {
+                    child.node()
+                } else {
+                    None
+                }
+            })
     }
 
-    fn create_match_result_if_child_is_leaf<'a, 'b>(child: &'a LiteralNode)
+    fn create_match_result_if_child_is_leaf<'a, 'b>(child: &'a LiteralNode,
+                                                    literal_len: usize)
                                                     -> Option<MatchResult<'a, 'b>> {
         if let Some(pattern) = child.pattern() {
-            let result = MatchResult::new(pattern);
+            let mut result = MatchResult::new(pattern);
+            result.add_literal_len(literal_len);
             Some(result)
         } else {
             info!("Early matching message: the message was too short to reach a leaf");
@@ -211,6 +285,38 @@ impl SuffixTree {
         }
     }
 
+    // Generates an example message for every pattern by concatenating the literals
+    // and the examples of the parsers on the path of the pattern. The patterns with
+    // parsers which can't give an example are skipped.
+    pub fn examples(&self) -> Vec<(&Pattern, String)> {
+        let mut examples = Vec::new();
+        self.collect_examples("", &mut examples);
+        examples
+    }
+
+    fn collect_examples<'a>(&'a self, prefix: &str, examples: &mut Vec<(&'a Pattern, String)>) {
+        for child in self.literal_children.iter() {
+            let text = format!("{}{}", prefix, child.literal());
+            if let Some(pattern) = child.pattern() {
+                examples.push((pattern, text.clone()));
+            }
+            if let Some(node) = child.node() {
+                node.collect_examples(&text, examples);
+            }
+        }
+        for child in &self.parser_children {
+            if let Some(example) = child.parser().example() {
+                let text = format!("{}{}", prefix, example);
+                if let Some(pattern) = child.pattern() {
+                    examples.push((pattern, text.clone()));
+                }
+                if let Some(node) = child.node() {
+                    node.collect_examples(&text, examples);
+                }
+            }
+        }
+    }
+
     fn lookup_parser(&mut self, parser: &Parser) -> Option<usize> {
         self.parser_children.iter().position(|ref x| x.parser().hash_os() == parser.hash_os())
     }
@@ -285,11 +391,68 @@ impl SuffixTree {
     }
 }
 
+impl Dump for SuffixTree {
+    fn dump(&self) -> DumpNode {
+        let mut root = DumpNode::root();
+        self.dump_children(&mut root);
+        root
+    }
+}
+
+impl SuffixTree {
+    fn dump_children(&self, parent: &mut DumpNode) {
+        for child in self.literal_children.iter() {
+            let mut node = DumpNode::literal(child.literal(),
+                                             child.pattern().map(|pattern| *pattern.uuid()));
+            if let Some(tree) = child.node() {
+                tree.dump_children(&mut node);
+            }
+            parent.push_child(node);
+        }
+        for child in &self.parser_children {
+            let mut node = DumpNode::parser(child.parser(),
+                                            child.pattern().map(|pattern| *pattern.uuid()));
+            if let Some(tree) = child.node() {
+                tree.dump_children(&mut node);
+            }
+            parent.push_child(node);
+        }
+    }
+}
+
+impl SuffixTree {
+    pub fn collect_stats(&self, depth: usize, stats: &mut MatcherStats) {
+        stats.visit_node(depth,
+                         self.literal_children.len(),
+                         self.parser_children.len(),
+                         mem::size_of::<SuffixTree>());
+        for child in self.literal_children.iter() {
+            stats.visit_literal(mem::size_of::<LiteralNode>() + child.literal().len());
+            if let Some(pattern) = child.pattern() {
+                stats.visit_pattern(pattern.min_memory());
+            }
+            if let Some(node) = child.node() {
+                node.collect_stats(depth + 1, stats);
+            }
+        }
+        for child in &self.parser_children {
+            stats.visit_parser(mem::size_of::<ParserNode>() + mem::size_of_val(child.parser()));
+            if let Some(pattern) = child.pattern() {
+                stats.visit_pattern(pattern.min_memory());
+            }
+            if let Some(node) = child.node() {
+                node.collect_stats(depth + 1, stats);
+            }
+        }
+    }
+}
+
 impl Default for SuffixTree {
     fn default() -> Self {
         SuffixTree {
             literal_children: SortedVec::new(),
             parser_children: Vec::new(),
+            prioritized: false,
         }
     }
 }
@@ -299,9 +462,13 @@ impl self::interface::SuffixTree for SuffixTree {
         SuffixTree {
             literal_children: SortedVec::new(),
             parser_children: Vec::new(),
+            prioritized: false,
         }
     }
     fn insert(&mut self, mut pattern: Pattern) {
+        if pattern.priority() != 0 {
+            self.prioritized = true;
+        }
         if let Some(token) = pattern.pop_first_token() {
             let mut entry: &mut Entry<ST = Self> = match token {
                 TokenType::Literal(literal) => {
@@ -324,6 +491,7 @@ mod test {
     use matcher::compiled_pattern::CompiledPatternBuilder;
     use matcher::pattern::Pattern;
     use matcher::trie::node::interface::SuffixTree as STree;
+    use matcher::Matcher;
 
     use std::iter::FromIterator;
     use std::collections::BTreeMap;
@@ -643,4 +811,261 @@ mod test {
             None => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_given_greedy_parser_when_the_end_string_occurs_in_the_value_then_its_mode_selects_where_the_value_ends
+        () {
+        let mut trie = SuffixTree::new();
+        for input in &[r#"from %{GREEDY(mode="successive"):from}: %{INT:n} s"#,
+                       r#"to %{GREEDY(mode="last"):to}: %{INT:n}"#,
+                       r#"via %{GREEDY:via}: %{INT:n}"#] {
+            let mut pattern = Pattern::with_random_uuid();
+            pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+            trie.insert(pattern);
+        }
+
+        let res = trie.parse("from a: b:c: 123 s").unwrap();
+        assert_eq!(res.values().get("from"), Some(&"a: b:c"));
+        let res = trie.parse("to a: b: 123").unwrap();
+        assert_eq!(res.values().get("to"), Some(&"a: b"));
+        assert_eq!(trie.parse("via a: b: 123").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_estring_parser_when_its_delimiter_is_consumed_then_the_trie_continues_after_it() {
+        let mut trie = SuffixTree::new();
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(r#"msg="%{ESTRING('"'):msg} end"#).unwrap());
+        trie.insert(pattern);
+
+        let res = trie.parse(r#"msg="a b c" end"#).unwrap();
+        assert_eq!(res.values().get("msg"), Some(&"a b c"));
+        assert_eq!(trie.parse(r#"msg="a b c end"#).is_none(), true);
+    }
+
+    #[test]
+    fn test_given_csv_parser_when_it_is_followed_by_a_literal_then_the_trie_continues_after_the_record
+        () {
+        let mut trie = SuffixTree::new();
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(r#"%{CSV("a", "b"):x} tail"#).unwrap());
+        trie.insert(pattern);
+
+        let res = trie.parse("1,2 tail").unwrap();
+        assert_eq!(res.values().get("x"), Some(&"1,2"));
+        assert_eq!(res.dynamic_values().get("x.b").map(|value| &value[..]), Some("2"));
+        assert_eq!(trie.parse("1,2 head").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_csv_parser_at_the_end_of_the_pattern_when_its_last_column_has_more_words_then_it_matches
+        () {
+        let mut trie = SuffixTree::new();
+        let mut pattern = Pattern::with_random_uuid();
+        pattern.set_pattern(::grammar::parser::pattern(r#"vendor: %{CSV("a","b","msg",delimiter="|"):r}"#)
+                                .unwrap());
+        trie.insert(pattern);
+
+        let res = trie.parse("vendor: 1|2|disk full on sda").unwrap();
+        assert_eq!(res.dynamic_values().get("r.msg").map(|value| &value[..]),
+                   Some("disk full on sda"));
+        assert_eq!(trie.parse("vendor: 1|2|diskfull").is_some(), true);
+    }
+
+    #[test]
+    fn test_given_literal_child_with_common_prefix_when_its_subtree_does_not_match_then_the_parser_children_are_tried
+        () {
+        let mut trie = SuffixTree::new();
+        for input in &["user %{INT:uid} logged in",
+                       "%{SET(\"abcdefghijklmnopqrstuvwxyz\"):name} %{INT:uid} logged out"] {
+            let mut pattern = Pattern::with_random_uuid();
+            pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+            trie.insert(pattern);
+        }
+
+        let res = trie.parse("user 0 logged in").unwrap();
+        assert_eq!(res.values().get("uid"), Some(&"0"));
+        let res = trie.parse("user 0 logged out").unwrap();
+        assert_eq!(res.values().get("name"), Some(&"user"));
+        assert_eq!(res.values().get("uid"), Some(&"0"));
+        assert_eq!(trie.parse("user 0 logged").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_literal_which_is_the_prefix_of_an_other_one_when_it_continues_with_a_parser_then_the_parser_is_tried
+        () {
+        let mut trie = SuffixTree::new();
+        for input in &["user root %{GREEDY:user}", "user%{INT:uid}"] {
+            let mut pattern = Pattern::with_random_uuid();
+            pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+            trie.insert(pattern);
+        }
+
+        let res = trie.parse("user1").unwrap();
+        assert_eq!(res.values().get("uid"), Some(&"1"));
+        let res = trie.parse("user root x").unwrap();
+        assert_eq!(res.values().get("user"), Some(&"x"));
+        assert_eq!(trie.parse("userx").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_case_sensitive_and_insensitive_literals_with_common_prefix_when_the_text_is_parsed_then_both_can_match
+        () {
+        let mut trie = SuffixTree::new();
+        for input in &["ERROR: disk %{INT:disk}",
+                       "ERROR: dns %{INT:dns}",
+                       "%{ICASE('error: ')}net %{INT:net}",
+                       "%{ICASE('ERROR: DISK')} full"] {
+            let mut pattern = Pattern::with_random_uuid();
+            pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+            trie.insert(pattern);
+        }
+        assert_eq!(trie.parser_children.len(), 2);
+
+        let res = trie.parse("ERROR: disk 1").unwrap();
+        assert_eq!(res.values().get("disk"), Some(&"1"));
+        let res = trie.parse("ERROR: net 2").unwrap();
+        assert_eq!(res.values().get("net"), Some(&"2"));
+        let res = trie.parse("Error: net 3").unwrap();
+        assert_eq!(res.values().get("net"), Some(&"3"));
+        assert_eq!(trie.parse("ERROR: disk full").is_some(), true);
+        assert_eq!(trie.parse("error: dns 4").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_whitespace_tolerant_pattern_when_the_whitespace_runs_of_the_message_differ_then_it_still_matches
+        () {
+        let mut trie = SuffixTree::new();
+        for &(input, tolerant) in &[("Accepted password for %{GREEDY:user} from", false),
+                                    ("Accepted  publickey for  %{INT:uid}", true),
+                                    ("Failed password for %{GREEDY:user} from %{INT:port}", true)] {
+            let mut pattern = Pattern::with_random_uuid();
+            pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+            pattern.set_whitespace_tolerant(tolerant);
+            trie.add_pattern(pattern);
+        }
+
+        let res = trie.parse("Accepted publickey\tfor   0").unwrap();
+        assert_eq!(res.values().get("uid"), Some(&"0"));
+        let res = trie.parse("Failed  password for root \t from 22").unwrap();
+        assert_eq!(res.values().get("user"), Some(&"root"));
+        assert_eq!(trie.parse("Accepted password for root from").is_some(), true);
+        assert_eq!(trie.parse("Accepted  password for root from").is_none(), true);
+        assert_eq!(trie.parse("Acceptedpublickey for 0").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_patterns_with_a_common_parser_prefix_when_they_are_inserted_then_none_of_them_is_lost
+        () {
+        let mut trie = SuffixTree::new();
+        for input in &["foo %{INT:a} x", "foo %{INT:a} y", "foo %{INT:a}"] {
+            let mut pattern = Pattern::with_random_uuid();
+            pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+            trie.insert(pattern);
+        }
+
+        assert_eq!(trie.parse("foo 1 x").is_some(), true);
+        assert_eq!(trie.parse("foo 1 y").is_some(), true);
+        assert_eq!(trie.parse("foo 1").is_some(), true);
+    }
+
+    #[test]
+    fn test_given_trie_when_more_patterns_match_then_the_one_with_the_highest_priority_wins() {
+        use matcher::Matcher;
+
+        let mut trie = SuffixTree::new();
+        for &(input, priority) in &[("user %{GREEDY:user} logged in", 1),
+                                    ("user %{INT:uid} logged in", 0),
+                                    ("user %{INT:uid} logged out", 0),
+                                    ("user %{GREEDY:user} logged out", -1),
+                                    ("user root logged out", 0)] {
+            let mut pattern = Pattern::with_random_uuid();
+            pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+            pattern.set_priority(priority);
+            trie.insert(pattern);
+        }
+        assert_eq!(trie.is_prioritized(), true);
+
+        let res = Matcher::parse(&trie, "user 0 logged in").unwrap();
+        assert_eq!(res.pattern().priority(), 1);
+        assert_eq!(res.values().get("user"), Some(&"0"));
+        let res = Matcher::parse(&trie, "user 0 logged out").unwrap();
+        assert_eq!(res.values().get("uid"), Some(&"0"));
+        let res = Matcher::parse(&trie, "user root logged out").unwrap();
+        assert_eq!(res.literal_len(), "user root logged out".len());
+        assert_eq!(res.values().is_empty(), true);
+    }
+
+    #[test]
+    fn test_given_trie_when_more_patterns_match_then_parse_all_returns_each_of_them_with_their_values
+        () {
+        use matcher::Matcher;
+
+        let mut trie = SuffixTree::new();
+        for input in &["from %{GREEDY(mode=\"successive\"):from}: %{INT:code}",
+                       "from %{INT:port}: %{INT:code}",
+                       "from 42: 0"] {
+            let mut pattern = Pattern::with_random_uuid();
+            pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+            trie.insert(pattern);
+        }
+
+        let results = Matcher::parse_all(&trie, "from 42: 0");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results.iter().filter(|res| res.values().get("port") == Some(&"42")).count(), 1);
+        assert_eq!(results.iter().filter(|res| res.values().get("from") == Some(&"42")).count(), 1);
+        let results = Matcher::parse_all(&trie, "from a: b: 1");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].values().get("from"), Some(&"a: b"));
+        assert_eq!(Matcher::parse_all(&trie, "from 42").is_empty(), true);
+    }
+
+    #[test]
+    fn test_given_trie_when_it_is_dumped_then_the_literals_the_parsers_and_the_patterns_are_shown() {
+        use matcher::dump::{Dump, write_text};
+        use uuid::Uuid;
+
+        let mut trie = SuffixTree::new();
+        for &(uuid, input) in &[("9a49c47d-29e9-4072-be84-3b76c6814743", "foo %{INT:a} x"),
+                                ("4b8f4d0e-0b6a-4f3e-8a5a-0f5a2d7c9e11", "foo %{INT:a}"),
+                                ("7c1e3a2b-6d4f-4a9b-8c2d-5e6f7a8b9c0d", "fox")] {
+            let mut pattern = Pattern::with_uuid(Uuid::parse_str(uuid).unwrap());
+            pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+            trie.insert(pattern);
+        }
+
+        let dump = trie.dump();
+        assert_eq!(dump.counts(), (4, 1, 3));
+        let mut out = Vec::new();
+        write_text(&dump, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(),
+                   "literal nodes: 4, parser nodes: 1, patterns: 3
+\"fo\"
+  \"o \"
+    %{INT:a} => 4b8f4d0e-0b6a-4f3e-8a5a-0f5a2d7c9e11
+      \" x\" => 9a49c47d-29e9-4072-be84-3b76c6814743
+  \"x\" => 7c1e3a2b-6d4f-4a9b-8c2d-5e6f7a8b9c0d
+");
+    }
+
+    #[test]
+    fn test_given_trie_when_its_stats_are_calculated_then_every_node_is_counted() {
+        use matcher::Matcher;
+
+        let mut trie = SuffixTree::new();
+        for input in &["foo %{INT:a} x", "foo %{INT:a}", "fox", "foo %{GREEDY:b} y"] {
+            let mut pattern = Pattern::with_random_uuid();
+            pattern.set_pattern(::grammar::parser::pattern(input).unwrap());
+            trie.insert(pattern);
+        }
+
+        let stats = trie.stats();
+        assert_eq!(stats.patterns(), 4);
+        assert_eq!(stats.literal_nodes(), 5);
+        assert_eq!(stats.parser_nodes(), 2);
+        assert_eq!(stats.max_depth(), 4);
+        assert_eq!(stats.average_branching_factor(), 7.0 / 5.0);
+        assert_eq!(stats.max_parser_siblings(), 2);
+        assert_eq!(stats.min_memory() > 0, true);
+    }
 }
@@ -34,22 +34,50 @@ impl ParserNode {
     }
 
     pub fn parse<'a, 'b>(&'a self, text: &'b str) -> Option<MatchResult<'a, 'b>> {
-        if let Some(parsed_kwpair) = self.parser.parse(text) {
+        let mut next_kwpair = self.parser.parse(text);
+
+        while let Some(parsed_kwpair) = next_kwpair {
             trace!("parse(): parsed_kwpair = {:?}", &parsed_kwpair);
-            let text = text.ltrunc(parsed_kwpair.value().len());
+            let parsed_len = parsed_kwpair.match_len();
+            let rest = text.ltrunc(parsed_len);
 
-            return match self.node() {
-                Some(node) => {
-                    node.parse_then_push_kvpair(text, parsed_kwpair)
-                }
-                None => {
-                    self.push_last_kvpair(text, parsed_kwpair)
-                }
+            let result = if rest.is_empty() && self.pattern.is_some() {
+                self.push_last_kvpair(rest, parsed_kwpair)
+            } else if let Some(node) = self.node() {
+                node.parse_then_push_kvpair(rest, parsed_kwpair)
+            } else {
+                None
             };
+            if result.is_some() {
+                return result;
+            }
+            next_kwpair = self.parser.parse_next(text, parsed_len);
         }
         None
     }
 
+    pub fn parse_all<'a, 'b>(&'a self, text: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        let mut results = Vec::new();
+        let mut next_kwpair = self.parser.parse(text);
+
+        while let Some(parsed_kwpair) = next_kwpair {
+            let parsed_len = parsed_kwpair.match_len();
+            let rest = text.ltrunc(parsed_len);
+
+            if self.pattern.is_some() {
+                results.extend(self.push_last_kvpair(rest, parsed_kwpair.clone()));
+            }
+            if let Some(node) = self.node() {
+                for mut result in node.parse_all(rest) {
+                    result.insert(parsed_kwpair.clone());
+                    results.push(result);
+                }
+            }
+            next_kwpair = self.parser.parse_next(text, parsed_len);
+        }
+        results
+    }
+
     fn push_last_kvpair<'a, 'b>(&'a self,
                                 text: &'b str,
                                 kvpair: ParseResult<'a, 'b>)
@@ -85,20 +113,10 @@ impl Entry for ParserNode {
 
 impl ParserEntry for ParserNode {
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<MatchResult<'a, 'b>> {
-        if let Some(parsed_kwpair) = self.parser.parse(value) {
-            trace!("parse(): parsed_kwpair = {:?}", &parsed_kwpair);
-            let text = value.ltrunc(parsed_kwpair.value().len());
-
-            return match self.node() {
-                Some(node) => {
-                    node.parse_then_push_kvpair(text, parsed_kwpair)
-                }
-                None => {
-                    self.push_last_kvpair(text, parsed_kwpair)
-                }
-            };
-        }
-        None
+        ParserNode::parse(self, value)
+    }
+    fn parse_all<'a, 'b>(&'a self, value: &'b str) -> Vec<MatchResult<'a, 'b>> {
+        ParserNode::parse_all(self, value)
     }
     fn parser(&self) -> &Box<Parser> {
         &self.parser
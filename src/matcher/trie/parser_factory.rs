@@ -1,4 +1,4 @@
-use parsers::{GreedyParser, IntParser, OptionalParameter, Parser, ParserFactory, SetParser,
+use parsers::{GreedyParser, GreedyMode, IntParser, OptionalParameter, Parser, ParserFactory, SetParser,
               HasLengthConstraint};
 
 macro_rules! set_optinal_param {
@@ -15,6 +15,7 @@ macro_rules! set_optinal_param {
                     _ => ()
                 }
             }
+            OptionalParameter::Str(..) => ()
         }
     }
 }
@@ -51,8 +52,18 @@ impl ParserFactory for TrieParserFactory {
         parser.set_name(name);
         Box::new(parser)
     }
-    fn new_greedy(name: Option<&str>, end_string: Option<&str>) -> Box<Parser> {
+    fn new_greedy<'a>(name: Option<&str>,
+                      end_string: Option<&str>,
+                      opt_params: Option<Vec<OptionalParameter<'a>>>)
+                      -> Box<Parser> {
         let mut parser = GreedyParser::new();
+        for param in opt_params.unwrap_or_default() {
+            if let OptionalParameter::Str("mode", mode) = param {
+                if let Some(mode) = GreedyMode::from_name(mode) {
+                    parser.set_mode(mode);
+                }
+            }
+        }
         let end_string = end_string.map(|string| string.to_owned());
         parser.set_end_string(end_string);
         let name = name.map(|name| name.to_owned());
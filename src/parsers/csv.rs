@@ -0,0 +1,314 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, ParserParams, ParamError,
+              ParserSyntax};
+
+#[derive(Clone, Debug, Hash)]
+pub struct CSVParser {
+    base: ParserBase,
+    // the columns with empty names are parsed but not emitted
+    columns: Vec<String>,
+    delimiter: char,
+    quote: Option<char>,
+    prefix: Option<String>,
+}
+
+impl CSVParser {
+    pub fn with_name(name: String, columns: Vec<String>) -> CSVParser {
+        let mut parser = CSVParser::new(columns);
+        parser.base = ParserBase::with_name(name);
+        parser
+    }
+
+    pub fn new(columns: Vec<String>) -> CSVParser {
+        CSVParser {
+            base: ParserBase::new(),
+            columns: columns,
+            delimiter: ',',
+            quote: Some('"'),
+            prefix: None,
+        }
+    }
+
+    pub fn from_params(params: ParserParams) -> Result<Box<Parser>, ParamError> {
+        try!(params.expect_known_keys(&["delimiter", "quote", "prefix"]));
+        if params.positional().is_empty() {
+            return Err(ParamError::WrongArgumentCount {
+                expected: 1,
+                got: 0,
+            });
+        }
+        let columns = params.positional().iter().map(|column| (*column).to_owned()).collect();
+        let mut parser = CSVParser::new(columns);
+        parser.set_name(params.name().map(|name| name.to_owned()));
+        if let Some(delimiter) = try!(params.string("delimiter")) {
+            match CSVParser::single_char(delimiter) {
+                Some(Some(delimiter)) => parser.set_delimiter(delimiter),
+                _ => return Err(ParamError::invalid_value("delimiter", delimiter)),
+            }
+        }
+        if let Some(quote) = try!(params.string("quote")) {
+            match CSVParser::single_char(quote) {
+                Some(quote) => parser.set_quote(quote),
+                None => return Err(ParamError::invalid_value("quote", quote)),
+            }
+        }
+        parser.set_prefix(try!(params.string("prefix")).map(|prefix| prefix.to_owned()));
+        Ok(Box::new(parser))
+    }
+
+    pub fn set_delimiter(&mut self, delimiter: char) {
+        self.delimiter = delimiter;
+    }
+
+    pub fn set_quote(&mut self, quote: Option<char>) {
+        self.quote = quote;
+    }
+
+    // if there is no prefix, the keys are prefixed by the parser's name and a dot
+    pub fn set_prefix(&mut self, prefix: Option<String>) {
+        self.prefix = prefix;
+    }
+
+    // returns None if the value is longer than one character
+    fn single_char(value: &str) -> Option<Option<char>> {
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (c, None) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn prefix(&self) -> String {
+        match (self.prefix.as_ref(), self.name()) {
+            (Some(prefix), _) => prefix.clone(),
+            (None, Some(name)) => format!("{}.", name),
+            (None, None) => String::new(),
+        }
+    }
+
+    // Returns the parsed field and its length, the field is at least min_len long. An
+    // unquoted field ends at the delimiter, at a newline or at the end of the value. The
+    // last one can end at any whitespace before that too, so the pattern can continue
+    // after the record: the shortest end is returned first, the longer ones are
+    // returned by parse_next().
+    fn parse_field(&self, value: &str, last: bool, min_len: usize) -> Option<(String, usize)> {
+        let (field, len) = match self.quote {
+            Some(quote) if value.starts_with(quote) => {
+                match CSVParser::parse_quoted_field(&value[quote.len_utf8()..], quote) {
+                    Some((field, len)) => (field, len + quote.len_utf8()),
+                    None => return None,
+                }
+            }
+            _ => {
+                let end = value.find(|c: char| c == self.delimiter || c == '\n')
+                               .unwrap_or(value.len());
+                let len = if last {
+                    value[..end]
+                        .char_indices()
+                        .find(|&(pos, c)| pos >= min_len && c.is_whitespace())
+                        .map_or(end, |(pos, _)| pos)
+                } else {
+                    end
+                };
+                (value[..len].to_owned(), len)
+            }
+        };
+        if len >= min_len {
+            Some((field, len))
+        } else {
+            None
+        }
+    }
+
+    // a quote character inside a quoted field is escaped by doubling it
+    fn parse_quoted_field(value: &str, quote: char) -> Option<(String, usize)> {
+        let mut field = String::new();
+        let mut chars = value.char_indices().peekable();
+
+        while let Some((pos, c)) = chars.next() {
+            if c != quote {
+                field.push(c);
+            } else if chars.peek().map(|&(_, next)| next) == Some(quote) {
+                field.push(quote);
+                chars.next();
+            } else {
+                return Some((field, pos + quote.len_utf8()));
+            }
+        }
+        None
+    }
+
+    // parses a record which is at least min_len long
+    fn parse_record<'a, 'b>(&'a self,
+                            value: &'b str,
+                            min_len: usize)
+                            -> Option<ParseResult<'a, 'b>> {
+        let mut fields = Vec::with_capacity(self.columns.len());
+        let mut match_len = 0;
+
+        for i in 0..self.columns.len() {
+            if i > 0 {
+                if value[match_len..].starts_with(self.delimiter) {
+                    match_len += self.delimiter.len_utf8();
+                } else {
+                    return None;
+                }
+            }
+            let last = i + 1 == self.columns.len();
+            let min_field_len = if last {
+                min_len.saturating_sub(match_len)
+            } else {
+                0
+            };
+            match self.parse_field(&value[match_len..], last, min_field_len) {
+                Some((field, len)) => {
+                    fields.push(field);
+                    match_len += len;
+                }
+                None => return None,
+            }
+        }
+
+        if value[match_len..].starts_with(self.delimiter) {
+            debug!("CSVParser: the record has more columns than expected: expected={}",
+                   self.columns.len());
+            return None;
+        }
+
+        let prefix = self.prefix();
+        let mut result = ParseResult::new(self, &value[..match_len]);
+        for (column, field) in self.columns.iter().zip(fields) {
+            if !column.is_empty() {
+                result.push_value(format!("{}{}", prefix, column), field);
+            }
+        }
+        Some(result)
+    }
+}
+
+impl ObjectSafeHash for CSVParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:csv".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Parser for CSVParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        self.parse_record(value, 0)
+    }
+
+    // the last unquoted field is extended to its next whitespace or to its end
+    fn parse_next<'a, 'b>(&'a self,
+                          value: &'b str,
+                          previous_len: usize)
+                          -> Option<ParseResult<'a, 'b>> {
+        self.parse_record(value, previous_len + 1)
+    }
+
+    fn syntax(&self) -> Option<ParserSyntax> {
+        let mut syntax = ParserSyntax::new("CSV", self.name());
+        for column in &self.columns {
+            syntax.positional(&column[..]);
+        }
+        if self.delimiter != ',' {
+            syntax.string("delimiter", self.delimiter.to_string());
+        }
+        if self.quote != Some('"') {
+            syntax.string("quote", self.quote.map_or(String::new(), |quote| quote.to_string()));
+        }
+        if let Some(ref prefix) = self.prefix {
+            syntax.string("prefix", &prefix[..]);
+        }
+        Some(syntax)
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{CSVParser, Parser};
+
+    fn columns(columns: &[&str]) -> Vec<String> {
+        columns.iter().map(|column| (*column).to_owned()).collect()
+    }
+
+    fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+        expected.iter().map(|&(key, value)| (key.to_owned(), value.to_owned())).collect()
+    }
+
+    #[test]
+    fn test_given_csv_parser_when_the_record_has_the_right_columns_then_every_named_column_is_emitted
+        () {
+        let parser = CSVParser::with_name("csv".to_owned(), columns(&["ip", "", "msg", "status"]));
+        let res = parser.parse(r#"1.2.3.4,-,"GET /a,b ""x""",200"#).unwrap();
+        assert_eq!(res.value(), r#"1.2.3.4,-,"GET /a,b ""x""",200"#);
+        assert_eq!(res.into_values(),
+                   pairs(&[("csv.ip", "1.2.3.4"),
+                           ("csv.msg", r#"GET /a,b "x""#),
+                           ("csv.status", "200")]));
+    }
+
+    #[test]
+    fn test_given_csv_parser_when_the_column_count_is_wrong_then_it_doesnt_match() {
+        let parser = CSVParser::new(columns(&["a", "b"]));
+        assert_eq!(parser.parse("1").is_none(), true);
+        assert_eq!(parser.parse("1,2,3").is_none(), true);
+        assert_eq!(parser.parse(r#"1,"2"#).is_none(), true);
+        assert_eq!(parser.parse(r#""1"x,2"#).is_none(), true);
+    }
+
+    #[test]
+    fn test_given_csv_parser_with_custom_delimiter_and_quote_when_the_record_is_parsed_then_they_are_used
+        () {
+        let mut parser = CSVParser::new(columns(&["vendor", "product"]));
+        parser.set_delimiter('|');
+        parser.set_quote(Some('\''));
+        parser.set_prefix(Some(".cef.".to_owned()));
+        let res = parser.parse("'Big|Co'|fw\nnext line").unwrap();
+        assert_eq!(res.value(), "'Big|Co'|fw");
+        assert_eq!(res.into_values(),
+                   pairs(&[(".cef.vendor", "Big|Co"), (".cef.product", "fw")]));
+    }
+
+    #[test]
+    fn test_given_csv_parser_when_the_last_column_is_followed_by_whitespace_then_it_ends_there() {
+        let parser = CSVParser::with_name("csv".to_owned(), columns(&["a", "b"]));
+        let res = parser.parse(r#"1 2,3 tail"#).unwrap();
+        assert_eq!(res.value(), "1 2,3");
+        assert_eq!(res.into_values(), pairs(&[("csv.a", "1 2"), ("csv.b", "3")]));
+        let res = parser.parse(r#"1,"2 3" tail"#).unwrap();
+        assert_eq!(res.value(), r#"1,"2 3""#);
+        assert_eq!(parser.parse_next(r#"1,"2 3" tail"#, 7).is_none(), true);
+    }
+
+    #[test]
+    fn test_given_csv_parser_when_the_last_column_contains_whitespace_then_its_longer_ends_are_returned_next
+        () {
+        let mut parser = CSVParser::with_name("r".to_owned(), columns(&["a", "b", "msg"]));
+        parser.set_delimiter('|');
+        let value = "1|2|disk full on sda";
+        assert_eq!(parser.parse(value).unwrap().value(), "1|2|disk");
+        assert_eq!(parser.parse_next(value, 8).unwrap().value(), "1|2|disk full");
+        assert_eq!(parser.parse_next(value, 13).unwrap().value(), "1|2|disk full on");
+        let res = parser.parse_next(value, 16).unwrap();
+        assert_eq!(res.value(), value);
+        assert_eq!(res.into_values(),
+                   pairs(&[("r.a", "1"), ("r.b", "2"), ("r.msg", "disk full on sda")]));
+        assert_eq!(parser.parse_next(value, value.len()).is_none(), true);
+    }
+}
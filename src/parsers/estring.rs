@@ -0,0 +1,126 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, ParserParams, ParamError,
+              ParserSyntax};
+
+// Parses everything until the first occurrence of its delimiter. The delimiter is
+// not part of the value, but it's consumed by default.
+#[derive(Clone, Debug, Hash)]
+pub struct EStringParser {
+    base: ParserBase,
+    delimiter: String,
+    consume_delimiter: bool,
+}
+
+impl EStringParser {
+    pub fn with_name(name: String, delimiter: &str) -> EStringParser {
+        let mut parser = EStringParser::new(delimiter);
+        parser.base = ParserBase::with_name(name);
+        parser
+    }
+
+    pub fn new(delimiter: &str) -> EStringParser {
+        EStringParser {
+            base: ParserBase::new(),
+            delimiter: delimiter.to_owned(),
+            consume_delimiter: true,
+        }
+    }
+
+    pub fn from_params(params: ParserParams) -> Result<Box<Parser>, ParamError> {
+        try!(params.expect_positional(1));
+        try!(params.expect_known_keys(&["consume"]));
+        let delimiter = params.positional()[0];
+        if delimiter.is_empty() {
+            return Err(ParamError::invalid_value("delimiter", delimiter));
+        }
+        let mut parser = EStringParser::new(delimiter);
+        parser.set_name(params.name().map(|name| name.to_owned()));
+        match try!(params.string("consume")) {
+            Some("true") | None => parser.set_consume_delimiter(true),
+            Some("false") => parser.set_consume_delimiter(false),
+            Some(value) => return Err(ParamError::invalid_value("consume", value)),
+        }
+        Ok(Box::new(parser))
+    }
+
+    // if it's false, the delimiter is left for the next token of the pattern
+    pub fn set_consume_delimiter(&mut self, consume_delimiter: bool) {
+        self.consume_delimiter = consume_delimiter;
+    }
+}
+
+impl ObjectSafeHash for EStringParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:estring".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Parser for EStringParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        value.find(&self.delimiter[..]).map(|pos| {
+            let mut result = ParseResult::new(self, &value[..pos]);
+            if self.consume_delimiter {
+                result.set_match_len(pos + self.delimiter.len());
+            }
+            result
+        })
+    }
+
+    fn example(&self) -> Option<String> {
+        if self.consume_delimiter {
+            Some(format!("x{}", self.delimiter))
+        } else {
+            Some("x".to_owned())
+        }
+    }
+
+    fn syntax(&self) -> Option<ParserSyntax> {
+        let mut syntax = ParserSyntax::new("ESTRING", self.name());
+        syntax.positional(&self.delimiter[..]);
+        if !self.consume_delimiter {
+            syntax.string("consume", "false");
+        }
+        Some(syntax)
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{EStringParser, Parser};
+
+    #[test]
+    fn test_given_estring_parser_when_the_delimiter_is_not_found_then_it_doesnt_match() {
+        let parser = EStringParser::new("\"");
+        assert_eq!(parser.parse("foo bar").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_estring_parser_when_the_delimiter_is_found_then_it_is_consumed_but_not_part_of_the_value
+        () {
+        let mut parser = EStringParser::with_name("user".to_owned(), "\" ");
+        let res = parser.parse("root\" logged in").unwrap();
+        assert_eq!(res.value(), "root");
+        assert_eq!(res.match_len(), 6);
+
+        parser.set_consume_delimiter(false);
+        let res = parser.parse("root\" logged in").unwrap();
+        assert_eq!(res.value(), "root");
+        assert_eq!(res.match_len(), 4);
+    }
+}
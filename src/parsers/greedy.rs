@@ -1,10 +1,44 @@
 use std::hash::{SipHasher, Hash, Hasher};
-use super::{ParserBase, Parser, ObjectSafeHash, ParseResult};
+use super::{ParserBase, Parser, ObjectSafeHash, ParseResult, ParserSyntax};
+use matcher::compiled_pattern::WHITESPACE;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
+pub enum GreedyMode {
+    // the value lasts until the first occurrence of the end string
+    First,
+    // the first occurrence is tried first, then the next ones if the rest of the
+    // pattern doesn't match
+    Successive,
+    // the value lasts until the last occurrence of the end string
+    Last,
+}
+
+impl GreedyMode {
+    pub fn from_name(name: &str) -> Option<GreedyMode> {
+        match name {
+            "first" => Some(GreedyMode::First),
+            "successive" => Some(GreedyMode::Successive),
+            "last" => Some(GreedyMode::Last),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            GreedyMode::First => "first",
+            GreedyMode::Successive => "successive",
+            GreedyMode::Last => "last",
+        }
+    }
+}
 
 #[derive(Clone, Debug, Hash)]
 pub struct GreedyParser {
     base: ParserBase,
     end_string: Option<String>,
+    mode: GreedyMode,
+    // any whitespace run of the value matches a whitespace run of the end string
+    whitespace_tolerant: bool,
 }
 
 impl GreedyParser {
@@ -12,6 +46,8 @@ impl GreedyParser {
         GreedyParser {
             base: ParserBase::with_name(name),
             end_string: None,
+            mode: GreedyMode::First,
+            whitespace_tolerant: false,
         }
     }
 
@@ -29,6 +65,59 @@ impl GreedyParser {
     pub fn set_end_string(&mut self, end_string: Option<String>) {
         self.end_string = end_string;
     }
+
+    pub fn set_mode(&mut self, mode: GreedyMode) {
+        self.mode = mode;
+    }
+
+    // returns the position of the first (or the last) occurrence of the end string
+    fn find_end(&self, value: &str, end_string: &str, last: bool) -> Option<usize> {
+        if !self.whitespace_tolerant {
+            return if last {
+                value.rfind(end_string)
+            } else {
+                value.find(end_string)
+            };
+        }
+        let mut positions = value.char_indices().map(|(pos, _)| pos);
+        let found = if last {
+            positions.rev().find(|&pos| GreedyParser::starts_with_tolerant(&value[pos..], end_string))
+        } else {
+            positions.find(|&pos| GreedyParser::starts_with_tolerant(&value[pos..], end_string))
+        };
+        // the whitespace before the occurrence belongs to the end string
+        found.map(|pos| {
+            if GreedyParser::is_whitespace(end_string) {
+                value[..pos].trim_right_matches(|c| WHITESPACE.contains(c)).len()
+            } else {
+                pos
+            }
+        })
+    }
+
+    fn starts_with_tolerant(value: &str, end_string: &str) -> bool {
+        let mut value = value;
+        let mut end_string = end_string;
+        while let Some(c) = end_string.chars().next() {
+            if WHITESPACE.contains(c) {
+                if !GreedyParser::is_whitespace(value) {
+                    return false;
+                }
+                value = value.trim_left_matches(|c| WHITESPACE.contains(c));
+                end_string = end_string.trim_left_matches(|c| WHITESPACE.contains(c));
+            } else if value.starts_with(c) {
+                value = &value[c.len_utf8()..];
+                end_string = &end_string[c.len_utf8()..];
+            } else {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn is_whitespace(value: &str) -> bool {
+        value.chars().next().map_or(false, |c| WHITESPACE.contains(c))
+    }
 }
 
 impl Default for GreedyParser {
@@ -36,6 +125,8 @@ impl Default for GreedyParser {
         GreedyParser {
             base: ParserBase::new(),
             end_string: None,
+            mode: GreedyMode::First,
+            whitespace_tolerant: false,
         }
     }
 }
@@ -52,12 +143,47 @@ impl ObjectSafeHash for GreedyParser {
 impl Parser for GreedyParser {
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
         if let Some(end_string) = self.end_string.as_ref() {
-            value.find(end_string).map(|pos| ParseResult::new(self, &value[..pos]))
+            self.find_end(value, end_string, self.mode == GreedyMode::Last)
+                .map(|pos| ParseResult::new(self, &value[..pos]))
         } else {
             Some(ParseResult::new(self, &value[..]))
         }
     }
 
+    fn parse_next<'a, 'b>(&'a self,
+                          value: &'b str,
+                          previous_len: usize)
+                          -> Option<ParseResult<'a, 'b>> {
+        if self.mode != GreedyMode::Successive {
+            return None;
+        }
+        self.end_string.as_ref().and_then(|end_string| {
+            value[previous_len..].chars().next().and_then(|c| {
+                let mut start = previous_len + c.len_utf8();
+                // the next occurrence can't start in the whitespace run of the previous one
+                if self.whitespace_tolerant && GreedyParser::is_whitespace(end_string) {
+                    start = value.len() -
+                            value[start..].trim_left_matches(|c| WHITESPACE.contains(c)).len();
+                }
+                self.find_end(&value[start..], end_string, false)
+                    .map(|pos| ParseResult::new(self, &value[..start + pos]))
+            })
+        })
+    }
+
+    fn example(&self) -> Option<String> {
+        Some("x".to_owned())
+    }
+
+    // the end string is the literal which follows the parser, so it's not written
+    fn syntax(&self) -> Option<ParserSyntax> {
+        let mut syntax = ParserSyntax::new("GREEDY", self.name());
+        if self.mode != GreedyMode::First {
+            syntax.string("mode", self.mode.name());
+        }
+        Some(syntax)
+    }
+
     fn name(&self) -> Option<&str> {
         self.base.name()
     }
@@ -66,6 +192,10 @@ impl Parser for GreedyParser {
         self.base.set_name(name);
     }
 
+    fn set_whitespace_tolerant(&mut self, whitespace_tolerant: bool) {
+        self.whitespace_tolerant = whitespace_tolerant;
+    }
+
     fn boxed_clone(&self) -> Box<Parser> {
         Box::new(self.clone())
     }
@@ -73,7 +203,7 @@ impl Parser for GreedyParser {
 
 #[cfg(test)]
 mod test {
-    use parsers::{GreedyParser, Parser};
+    use parsers::{GreedyParser, GreedyMode, Parser};
 
     #[test]
     fn test_given_greedy_parser_when_the_end_string_is_not_found_in_the_value_then_the_parser_doesnt_match
@@ -90,4 +220,39 @@ mod test {
         assert_eq!(res.parser().name(), Some("name"));
         assert_eq!(res.value(), "qux ");
     }
+
+    #[test]
+    fn test_given_greedy_parser_when_the_end_string_occurs_more_than_once_then_the_mode_selects_the_occurrence
+        () {
+        let mut parser = GreedyParser::from_str("name", ": ");
+        let value = "a: b: c: 123";
+        assert_eq!(parser.parse(value).unwrap().value(), "a");
+        assert_eq!(parser.parse_next(value, 1).is_none(), true);
+
+        parser.set_mode(GreedyMode::Last);
+        assert_eq!(parser.parse(value).unwrap().value(), "a: b: c");
+
+        parser.set_mode(GreedyMode::Successive);
+        assert_eq!(parser.parse(value).unwrap().value(), "a");
+        assert_eq!(parser.parse_next(value, 1).unwrap().value(), "a: b");
+        assert_eq!(parser.parse_next(value, 4).unwrap().value(), "a: b: c");
+        assert_eq!(parser.parse_next(value, 7).is_none(), true);
+    }
+
+    #[test]
+    fn test_given_whitespace_tolerant_greedy_parser_when_the_whitespace_of_the_end_string_differs_then_it_still_matches
+        () {
+        let mut parser = GreedyParser::from_str("name", " from ");
+        parser.set_whitespace_tolerant(true);
+        let value = "zts \t from\t1 from 2";
+        assert_eq!(parser.parse(value).unwrap().value(), "zts");
+        assert_eq!(parser.parse("zts fromx").is_none(), true);
+
+        parser.set_mode(GreedyMode::Last);
+        assert_eq!(parser.parse(value).unwrap().value(), "zts \t from\t1");
+
+        parser.set_mode(GreedyMode::Successive);
+        assert_eq!(parser.parse_next(value, 3).unwrap().value(), "zts \t from\t1");
+        assert_eq!(parser.parse_next(value, 14).is_none(), true);
+    }
 }
@@ -0,0 +1,139 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, ParserParams, ParamError,
+              ParserSyntax};
+
+// Matches a literal case-insensitively. Case-insensitive literals are stored as
+// parsers, so they don't interfere with the ordering of the literals in the trie and
+// in the suffix array.
+#[derive(Clone, Debug)]
+pub struct CaseInsensitiveParser {
+    base: ParserBase,
+    // the literal as it's written, it's kept for syntax() and example()
+    literal: String,
+    // the literal is compared and hashed lowercase, so literals which differ only in
+    // case share the same node
+    lowercase: String,
+}
+
+impl CaseInsensitiveParser {
+    pub fn new(literal: &str) -> CaseInsensitiveParser {
+        CaseInsensitiveParser {
+            base: ParserBase::new(),
+            literal: literal.to_owned(),
+            lowercase: literal.to_lowercase(),
+        }
+    }
+
+    pub fn from_params(params: ParserParams) -> Result<Box<Parser>, ParamError> {
+        try!(params.expect_positional(1));
+        try!(params.expect_known_keys(&[]));
+        let literal = params.positional()[0];
+        if literal.is_empty() {
+            return Err(ParamError::invalid_value("literal", literal));
+        }
+        let mut parser = CaseInsensitiveParser::new(literal);
+        parser.set_name(params.name().map(|name| name.to_owned()));
+        Ok(Box::new(parser))
+    }
+
+    pub fn literal(&self) -> &str {
+        &self.literal
+    }
+
+    // returns the length of the matching prefix of the value
+    fn match_len(&self, value: &str) -> Option<usize> {
+        let mut value_chars = value.char_indices();
+        let mut literal_chars = self.lowercase.chars().peekable();
+
+        while literal_chars.peek().is_some() {
+            let c = match value_chars.next() {
+                Some((_, c)) => c,
+                None => return None,
+            };
+            for lower in c.to_lowercase() {
+                if literal_chars.next() != Some(lower) {
+                    return None;
+                }
+            }
+        }
+        Some(value_chars.next().map_or(value.len(), |(pos, _)| pos))
+    }
+}
+
+impl Hash for CaseInsensitiveParser {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.base.hash(state);
+        self.lowercase.hash(state);
+    }
+}
+
+impl ObjectSafeHash for CaseInsensitiveParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:icase".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Parser for CaseInsensitiveParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        self.match_len(value).map(|len| ParseResult::new(self, &value[..len]))
+    }
+
+    fn example(&self) -> Option<String> {
+        Some(self.literal.clone())
+    }
+
+    fn syntax(&self) -> Option<ParserSyntax> {
+        let mut syntax = ParserSyntax::new("ICASE", self.name());
+        syntax.positional(&self.literal[..]);
+        Some(syntax)
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{CaseInsensitiveParser, Parser, ObjectSafeHash};
+
+    #[test]
+    fn test_given_case_insensitive_parser_when_the_value_differs_only_in_case_then_it_matches() {
+        let parser = CaseInsensitiveParser::new("Error:");
+        assert_eq!(parser.parse("ERROR: disk").unwrap().value(), "ERROR:");
+        assert_eq!(parser.parse("error: disk").unwrap().value(), "error:");
+        assert_eq!(parser.parse("ÉRROR:").is_none(), true);
+        assert_eq!(parser.parse("Err").is_none(), true);
+        assert_eq!(CaseInsensitiveParser::new("ÁRVÍZ").parse("árvíz tűrő").unwrap().value(),
+                   "árvíz");
+    }
+
+    #[test]
+    fn test_given_case_insensitive_parsers_when_their_literals_differ_only_in_case_then_their_hashes_are_equal
+        () {
+        assert_eq!(CaseInsensitiveParser::new("ERROR").hash_os(),
+                   CaseInsensitiveParser::new("error").hash_os());
+        assert!(CaseInsensitiveParser::new("error").hash_os() !=
+                CaseInsensitiveParser::new("errors").hash_os());
+    }
+
+    #[test]
+    fn test_given_case_insensitive_parser_when_it_is_written_then_the_literal_keeps_its_case() {
+        let parser = CaseInsensitiveParser::new("Error:");
+        assert_eq!(parser.literal(), "Error:");
+        assert_eq!(parser.syntax().unwrap().to_string(), r#"%{ICASE("Error:")}"#);
+        assert_eq!(parser.example(), Some("Error:".to_owned()));
+    }
+}
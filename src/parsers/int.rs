@@ -1,6 +1,7 @@
 use std::hash::{SipHasher, Hash, Hasher};
 
-use parsers::{Parser, ObjectSafeHash, SetParser, ParseResult, HasLengthConstraint};
+use parsers::{Parser, ObjectSafeHash, SetParser, ParseResult, HasLengthConstraint, ValueType,
+              ParserSyntax};
 
 #[derive(Clone, Debug, Hash)]
 pub struct IntParser {
@@ -20,7 +21,8 @@ impl IntParser {
 
 impl Parser for IntParser {
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
-        self.delegate.parse(value)
+        // the result has to refer to this parser to get the right value type
+        self.delegate.parse(value).map(|result| ParseResult::new(self, result.value()))
     }
 
     fn name(&self) -> Option<&str> {
@@ -31,6 +33,25 @@ impl Parser for IntParser {
         self.delegate.set_name(name);
     }
 
+    fn value_type(&self) -> ValueType {
+        ValueType::Int
+    }
+
+    fn example(&self) -> Option<String> {
+        self.delegate.example()
+    }
+
+    fn syntax(&self) -> Option<ParserSyntax> {
+        let mut syntax = ParserSyntax::new("INT", self.name());
+        if let Some(min_length) = self.min_length() {
+            syntax.int("min_len", min_length);
+        }
+        if let Some(max_length) = self.max_length() {
+            syntax.int("max_len", max_length);
+        }
+        Some(syntax)
+    }
+
     fn boxed_clone(&self) -> Box<Parser> {
         Box::new(self.clone())
     }
@@ -68,7 +89,7 @@ impl ObjectSafeHash for IntParser {
 
 #[cfg(test)]
 mod test {
-    use parsers::{IntParser, Parser, HasLengthConstraint};
+    use parsers::{IntParser, Parser, HasLengthConstraint, ValueType};
 
     #[test]
     fn test_given_int_parser_when_the_match_is_empty_then_the_result_isnt_successful() {
@@ -84,6 +105,7 @@ mod test {
         let res = parser.parse("1234asd").unwrap();
         assert_eq!(res.parser().name(), Some(parser_name));
         assert_eq!(res.value(), "1234");
+        assert_eq!(res.parser().value_type(), ValueType::Int);
     }
 
     #[test]
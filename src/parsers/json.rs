@@ -0,0 +1,302 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use serde_json::{self, Value};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, ParserParams, ParamError,
+              ParserSyntax};
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
+pub enum ArrayMode {
+    // every element is emitted with its index as the last key segment
+    Index,
+    // the array is emitted as one JSON encoded value
+    Json,
+    // arrays are not emitted
+    Skip,
+}
+
+#[derive(Clone, Debug, Hash)]
+pub struct JSONParser {
+    base: ParserBase,
+    prefix: Option<String>,
+    max_depth: Option<usize>,
+    array_mode: ArrayMode,
+}
+
+impl JSONParser {
+    pub fn with_name(name: String) -> JSONParser {
+        let mut parser = JSONParser::new();
+        parser.base = ParserBase::with_name(name);
+        parser
+    }
+
+    pub fn new() -> JSONParser {
+        JSONParser::default()
+    }
+
+    pub fn from_params(params: ParserParams) -> Result<Box<Parser>, ParamError> {
+        try!(params.expect_positional(0));
+        try!(params.expect_known_keys(&["prefix", "max_depth", "arrays"]));
+        let prefix = try!(params.string("prefix"));
+        // the keys of an unnamed parser without a prefix could collide with anything
+        if params.name().is_none() && prefix.is_none() {
+            return Err(ParamError::MissingParameter("prefix".to_owned()));
+        }
+        let mut parser = JSONParser::new();
+        parser.set_name(params.name().map(|name| name.to_owned()));
+        parser.set_prefix(prefix.map(|prefix| prefix.to_owned()));
+        parser.set_max_depth(try!(params.int("max_depth")));
+        if let Some(mode) = try!(params.string("arrays")) {
+            let mode = match mode {
+                "index" => ArrayMode::Index,
+                "json" => ArrayMode::Json,
+                "skip" => ArrayMode::Skip,
+                _ => return Err(ParamError::invalid_value("arrays", mode)),
+            };
+            parser.set_array_mode(mode);
+        }
+        Ok(Box::new(parser))
+    }
+
+    // if there is no prefix, the keys are prefixed by the parser's name and a dot
+    pub fn set_prefix(&mut self, prefix: Option<String>) {
+        self.prefix = prefix;
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: Option<usize>) {
+        self.max_depth = max_depth;
+    }
+
+    pub fn set_array_mode(&mut self, mode: ArrayMode) {
+        self.array_mode = mode;
+    }
+
+    fn prefix(&self) -> String {
+        match (self.prefix.as_ref(), self.name()) {
+            (Some(prefix), _) => prefix.clone(),
+            (None, Some(name)) => format!("{}.", name),
+            (None, None) => String::new(),
+        }
+    }
+
+    // returns the length of the object or array at the beginning of the value
+    fn balanced_len(value: &str) -> Option<usize> {
+        let mut depth = 0;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        for (pos, c) in value.char_indices() {
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    in_string = false;
+                }
+                continue;
+            }
+            match c {
+                '"' => in_string = true,
+                '{' | '[' => depth += 1,
+                '}' | ']' if depth == 0 => return None,
+                '}' | ']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(pos + 1);
+                    }
+                }
+                _ if depth == 0 => return None,
+                _ => (),
+            }
+        }
+        None
+    }
+
+    fn flatten(&self, key: String, value: &Value, depth: usize, result: &mut ParseResult) {
+        let depth_exceeded = self.max_depth.map_or(false, |max_depth| depth >= max_depth);
+
+        match *value {
+            Value::Null => (),
+            Value::String(ref string) => result.push_value(key, string.clone()),
+            Value::Object(ref object) => {
+                if depth_exceeded {
+                    result.push_value(key, value.to_string());
+                } else {
+                    for (child_key, child) in object {
+                        self.flatten(JSONParser::join(&key, child_key), child, depth + 1, result);
+                    }
+                }
+            }
+            Value::Array(ref array) => {
+                match self.array_mode {
+                    ArrayMode::Skip => (),
+                    ArrayMode::Json => result.push_value(key, value.to_string()),
+                    ArrayMode::Index if depth_exceeded => {
+                        result.push_value(key, value.to_string())
+                    }
+                    ArrayMode::Index => {
+                        for (index, child) in array.iter().enumerate() {
+                            let child_key = JSONParser::join(&key, &index.to_string());
+                            self.flatten(child_key, child, depth + 1, result);
+                        }
+                    }
+                }
+            }
+            _ => result.push_value(key, value.to_string()),
+        }
+    }
+
+    fn join(key: &str, child_key: &str) -> String {
+        if key.is_empty() || key.ends_with('.') {
+            format!("{}{}", key, child_key)
+        } else {
+            format!("{}.{}", key, child_key)
+        }
+    }
+}
+
+impl Default for JSONParser {
+    fn default() -> Self {
+        JSONParser {
+            base: ParserBase::new(),
+            prefix: None,
+            max_depth: None,
+            array_mode: ArrayMode::Index,
+        }
+    }
+}
+
+impl ObjectSafeHash for JSONParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:json".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Parser for JSONParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        let len = match JSONParser::balanced_len(value) {
+            Some(len) => len,
+            None => return None,
+        };
+        let json = &value[..len];
+
+        match serde_json::from_str::<Value>(json) {
+            Ok(parsed) => {
+                let mut result = ParseResult::new(self, json);
+                self.flatten(self.prefix(), &parsed, 0, &mut result);
+                Some(result)
+            }
+            Err(err) => {
+                debug!("JSONParser: failed to parse a balanced JSON value: error={}", err);
+                None
+            }
+        }
+    }
+
+    fn syntax(&self) -> Option<ParserSyntax> {
+        let mut syntax = ParserSyntax::new("JSON", self.name());
+        if let Some(ref prefix) = self.prefix {
+            syntax.string("prefix", &prefix[..]);
+        }
+        if let Some(max_depth) = self.max_depth {
+            syntax.int("max_depth", max_depth);
+        }
+        match self.array_mode {
+            ArrayMode::Index => (),
+            ArrayMode::Json => {
+                syntax.string("arrays", "json");
+            }
+            ArrayMode::Skip => {
+                syntax.string("arrays", "skip");
+            }
+        }
+        Some(syntax)
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{JSONParser, Parser, ParserParams, ParamError, OptionalParameter};
+    use parsers::json::ArrayMode;
+
+    fn values(parser: &JSONParser, value: &str) -> Vec<(String, String)> {
+        parser.parse(value).unwrap().into_values()
+    }
+
+    fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+        expected.iter().map(|&(key, value)| (key.to_owned(), value.to_owned())).collect()
+    }
+
+    #[test]
+    fn test_given_json_parser_when_the_value_is_not_a_balanced_json_value_then_it_doesnt_match() {
+        let parser = JSONParser::new();
+        assert_eq!(parser.parse("user=x").is_none(), true);
+        assert_eq!(parser.parse(r#"{"user":"x""#).is_none(), true);
+        assert_eq!(parser.parse(r#"{"user":x}"#).is_none(), true);
+        assert_eq!(parser.parse("}{}").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_json_parser_when_the_value_is_an_object_then_it_is_flattened_with_the_name_as_prefix
+        () {
+        let parser = JSONParser::with_name(".json".to_owned());
+        let res = parser.parse(r#"{"user":"x}","dur":3,"ok":true,"n":null,"a":{"b":[1,2]}} tail"#)
+                        .unwrap();
+        assert_eq!(res.value(), r#"{"user":"x}","dur":3,"ok":true,"n":null,"a":{"b":[1,2]}}"#);
+        assert_eq!(res.into_values(),
+                   pairs(&[(".json.a.b.0", "1"),
+                           (".json.a.b.1", "2"),
+                           (".json.dur", "3"),
+                           (".json.ok", "true"),
+                           (".json.user", "x}")]));
+    }
+
+    #[test]
+    fn test_given_json_parser_when_max_depth_and_array_mode_are_set_then_they_are_respected() {
+        let mut parser = JSONParser::new();
+        parser.set_prefix(Some("j.".to_owned()));
+        parser.set_max_depth(Some(1));
+        assert_eq!(values(&parser, r#"{"a":{"b":1},"c":[1]}"#),
+                   pairs(&[("j.a", r#"{"b":1}"#), ("j.c", "[1]")]));
+
+        parser.set_max_depth(None);
+        parser.set_array_mode(ArrayMode::Skip);
+        assert_eq!(values(&parser, r#"{"a":{"b":1},"c":[1]}"#), pairs(&[("j.a.b", "1")]));
+
+        parser.set_array_mode(ArrayMode::Json);
+        assert_eq!(values(&parser, r#"{"c":[1,"x"]}"#), pairs(&[("j.c", r#"[1,"x"]"#)]));
+    }
+
+    #[test]
+    fn test_given_json_parser_params_when_there_is_neither_a_name_nor_a_prefix_then_it_is_rejected
+        () {
+        let err = JSONParser::from_params(ParserParams::new(None)).err().unwrap();
+        assert_eq!(err, ParamError::MissingParameter("prefix".to_owned()));
+
+        let mut params = ParserParams::new(None);
+        params.push_optional(OptionalParameter::Str("prefix", "j."));
+        let parser = JSONParser::from_params(params).unwrap();
+        assert_eq!(parser.parse(r#"{"user":"x"}"#).unwrap().into_values(),
+                   pairs(&[("j.user", "x")]));
+
+        let parser = JSONParser::from_params(ParserParams::new(Some("json"))).unwrap();
+        assert_eq!(parser.parse(r#"{"user":"x"}"#).unwrap().into_values(),
+                   pairs(&[("json.user", "x")]));
+    }
+}
@@ -0,0 +1,261 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, ParserParams, ParamError,
+              ParserSyntax};
+
+#[derive(Clone, Debug, Hash)]
+pub struct KVParser {
+    base: ParserBase,
+    pair_separator: String,
+    kv_separator: String,
+    quote: Option<char>,
+    prefix: Option<String>,
+}
+
+impl KVParser {
+    pub fn with_name(name: String) -> KVParser {
+        let mut parser = KVParser::new();
+        parser.base = ParserBase::with_name(name);
+        parser
+    }
+
+    pub fn new() -> KVParser {
+        KVParser::default()
+    }
+
+    pub fn from_params(params: ParserParams) -> Result<Box<Parser>, ParamError> {
+        try!(params.expect_positional(0));
+        try!(params.expect_known_keys(&["pair_separator", "kv_separator", "quote", "prefix"]));
+        let mut parser = KVParser::new();
+        parser.set_name(params.name().map(|name| name.to_owned()));
+        if let Some(separator) = try!(params.string("pair_separator")) {
+            if separator.is_empty() {
+                return Err(ParamError::invalid_value("pair_separator", separator));
+            }
+            parser.set_pair_separator(separator);
+        }
+        if let Some(separator) = try!(params.string("kv_separator")) {
+            if separator.is_empty() {
+                return Err(ParamError::invalid_value("kv_separator", separator));
+            }
+            parser.set_kv_separator(separator);
+        }
+        if let Some(quote) = try!(params.string("quote")) {
+            let mut chars = quote.chars();
+            match (chars.next(), chars.next()) {
+                (quote, None) => parser.set_quote(quote),
+                _ => return Err(ParamError::invalid_value("quote", quote)),
+            }
+        }
+        parser.set_prefix(try!(params.string("prefix")).map(|prefix| prefix.to_owned()));
+        Ok(Box::new(parser))
+    }
+
+    pub fn set_pair_separator<S: Into<String>>(&mut self, separator: S) {
+        self.pair_separator = separator.into();
+    }
+
+    pub fn set_kv_separator<S: Into<String>>(&mut self, separator: S) {
+        self.kv_separator = separator.into();
+    }
+
+    pub fn set_quote(&mut self, quote: Option<char>) {
+        self.quote = quote;
+    }
+
+    pub fn set_prefix(&mut self, prefix: Option<String>) {
+        self.prefix = prefix;
+    }
+
+    fn key_with_prefix(&self, key: &str) -> String {
+        match self.prefix {
+            Some(ref prefix) => format!("{}{}", prefix, key),
+            None => key.to_owned(),
+        }
+    }
+
+    // returns the parsed key, value and the length of the pair
+    fn parse_pair(&self, value: &str) -> Option<(String, String, usize)> {
+        let key_len = match value.find(&self.kv_separator[..]) {
+            Some(pos) => pos,
+            None => return None,
+        };
+        let key = &value[..key_len];
+        if key.is_empty() || key.contains(&self.pair_separator[..]) {
+            return None;
+        }
+
+        let value_start = key_len + self.kv_separator.len();
+        let rest = &value[value_start..];
+        let (parsed_value, value_len) = match self.quote {
+            Some(quote) if rest.starts_with(quote) => {
+                match KVParser::parse_quoted_value(&rest[quote.len_utf8()..], quote) {
+                    Some((parsed_value, len)) => (parsed_value, len + quote.len_utf8()),
+                    None => return None,
+                }
+            }
+            _ => {
+                let len = rest.find(&self.pair_separator[..]).unwrap_or(rest.len());
+                (rest[..len].to_owned(), len)
+            }
+        };
+        Some((key.to_owned(), parsed_value, value_start + value_len))
+    }
+
+    // the value has to be terminated by an unescaped quote character
+    fn parse_quoted_value(value: &str, quote: char) -> Option<(String, usize)> {
+        let mut parsed_value = String::new();
+        let mut escaped = false;
+
+        for (pos, c) in value.char_indices() {
+            if escaped {
+                if c != quote && c != '\\' {
+                    parsed_value.push('\\');
+                }
+                parsed_value.push(c);
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == quote {
+                return Some((parsed_value, pos + quote.len_utf8()));
+            } else {
+                parsed_value.push(c);
+            }
+        }
+        None
+    }
+}
+
+impl Default for KVParser {
+    fn default() -> Self {
+        KVParser {
+            base: ParserBase::new(),
+            pair_separator: " ".to_owned(),
+            kv_separator: "=".to_owned(),
+            quote: Some('"'),
+            prefix: None,
+        }
+    }
+}
+
+impl ObjectSafeHash for KVParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:kv".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Parser for KVParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        let mut pairs = Vec::new();
+        let mut match_len = 0;
+
+        loop {
+            let pair_start = if pairs.is_empty() {
+                match_len
+            } else if value[match_len..].starts_with(&self.pair_separator[..]) {
+                match_len + self.pair_separator.len()
+            } else {
+                break;
+            };
+
+            match self.parse_pair(&value[pair_start..]) {
+                Some((key, parsed_value, pair_len)) => {
+                    pairs.push((self.key_with_prefix(&key), parsed_value));
+                    match_len = pair_start + pair_len;
+                }
+                None => break,
+            }
+        }
+
+        if pairs.is_empty() {
+            None
+        } else {
+            let mut result = ParseResult::new(self, &value[..match_len]);
+            for (key, parsed_value) in pairs {
+                result.push_value(key, parsed_value);
+            }
+            Some(result)
+        }
+    }
+
+    // only the parameters which differ from the defaults are written
+    fn syntax(&self) -> Option<ParserSyntax> {
+        let default = KVParser::default();
+        let mut syntax = ParserSyntax::new("KV", self.name());
+        if self.pair_separator != default.pair_separator {
+            syntax.string("pair_separator", &self.pair_separator[..]);
+        }
+        if self.kv_separator != default.kv_separator {
+            syntax.string("kv_separator", &self.kv_separator[..]);
+        }
+        if self.quote != default.quote {
+            syntax.string("quote", self.quote.map_or(String::new(), |quote| quote.to_string()));
+        }
+        if let Some(ref prefix) = self.prefix {
+            syntax.string("prefix", &prefix[..]);
+        }
+        Some(syntax)
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{KVParser, Parser};
+
+    fn pairs(parser: &KVParser, value: &str) -> Vec<(String, String)> {
+        parser.parse(value).unwrap().into_values()
+    }
+
+    #[test]
+    fn test_given_kv_parser_when_the_value_does_not_contain_pairs_then_the_parser_doesnt_match() {
+        let parser = KVParser::new();
+        assert_eq!(parser.parse("foo bar").is_none(), true);
+        assert_eq!(parser.parse("=bar").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_kv_parser_when_the_value_contains_pairs_then_every_pair_is_emitted() {
+        let parser = KVParser::with_name("kv".to_owned());
+        let res = parser.parse(r#"src=1.2.3.4 msg="v 2" dst=5.6.7.8 trailing text"#).unwrap();
+        assert_eq!(res.parser().name(), Some("kv"));
+        assert_eq!(res.value(), r#"src=1.2.3.4 msg="v 2" dst=5.6.7.8"#);
+        assert_eq!(res.into_values(),
+                   vec![("src".to_owned(), "1.2.3.4".to_owned()),
+                        ("msg".to_owned(), "v 2".to_owned()),
+                        ("dst".to_owned(), "5.6.7.8".to_owned())]);
+    }
+
+    #[test]
+    fn test_given_kv_parser_with_custom_separators_when_the_value_is_parsed_then_they_are_used() {
+        let mut parser = KVParser::new();
+        parser.set_pair_separator(", ");
+        parser.set_kv_separator(":");
+        parser.set_quote(Some('\''));
+        parser.set_prefix(Some(".fw.".to_owned()));
+        assert_eq!(pairs(&parser, r"a:1, b:'x, \'y\''"),
+                   vec![(".fw.a".to_owned(), "1".to_owned()),
+                        (".fw.b".to_owned(), "x, 'y'".to_owned())]);
+    }
+
+    #[test]
+    fn test_given_kv_parser_when_a_quoted_value_is_not_terminated_then_the_pair_is_not_consumed() {
+        let parser = KVParser::new();
+        let res = parser.parse(r#"a=1 b="2"#).unwrap();
+        assert_eq!(res.value(), "a=1");
+    }
+}
@@ -3,13 +3,34 @@ mod base;
 mod int;
 pub mod has_length_constraint;
 mod greedy;
+mod kv;
+pub mod json;
+mod csv;
+pub mod timestamp;
+mod typed;
+mod estring;
+mod icase;
+mod syntax;
+mod value;
+pub mod registry;
 
 use std::fmt::Debug;
 pub use self::set::SetParser;
 pub use self::base::ParserBase;
 pub use self::int::IntParser;
 pub use self::has_length_constraint::HasLengthConstraint;
-pub use self::greedy::GreedyParser;
+pub use self::greedy::{GreedyParser, GreedyMode};
+pub use self::kv::KVParser;
+pub use self::json::JSONParser;
+pub use self::csv::CSVParser;
+pub use self::timestamp::TimestampParser;
+pub use self::typed::TypedParser;
+pub use self::estring::EStringParser;
+pub use self::icase::CaseInsensitiveParser;
+pub use self::syntax::{ParserSyntax, SyntaxValue};
+pub use self::value::{ValueType, TypedValue, ConversionError};
+pub use self::registry::{ParserRegistry, ParserParams, ParserArgument, ParamError,
+                         ParserConstructor, register_parser};
 
 pub trait ObjectSafeHash {
     fn hash_os(&self) -> u64;
@@ -17,20 +38,55 @@ pub trait ObjectSafeHash {
 
 pub trait Parser: Debug + ObjectSafeHash {
     fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>>;
+    // parsers which can match in more than one way return the next, longer match
+    // here, it's used when the rest of the pattern doesn't match after the previous one
+    // (the previous length is the match_len() of the previous result)
+    fn parse_next<'a, 'b>(&'a self,
+                          _value: &'b str,
+                          _previous_len: usize)
+                          -> Option<ParseResult<'a, 'b>> {
+        None
+    }
     fn name(&self) -> Option<&str>;
     fn set_name(&mut self, Option<String>);
+    fn value_type(&self) -> ValueType {
+        ValueType::String
+    }
+    // a text which is matched by the parser, it's used to generate example messages
+    fn example(&self) -> Option<String> {
+        None
+    }
+    // the parser as it's written in a pattern, the parsers which can't be written in
+    // a pattern return None
+    fn syntax(&self) -> Option<ParserSyntax> {
+        None
+    }
+    // it's called for the parsers of whitespace tolerant patterns, the parsers which
+    // look for a literal should accept any whitespace run in place of a whitespace run
+    fn set_whitespace_tolerant(&mut self, _whitespace_tolerant: bool) {}
     fn boxed_clone(&self) -> Box<Parser>;
 }
 
 #[derive(Debug)]
 pub enum OptionalParameter<'a> {
     Int(&'a str, usize),
+    Str(&'a str, &'a str),
 }
 
-#[derive(Debug)]
+impl<'a> OptionalParameter<'a> {
+    pub fn key(&self) -> &'a str {
+        match *self {
+            OptionalParameter::Int(key, _) | OptionalParameter::Str(key, _) => key,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct ParseResult<'a, 'b> {
     parser: &'a Parser,
     value: &'b str,
+    match_len: usize,
+    values: Vec<(String, String)>,
 }
 
 impl<'a, 'b> ParseResult<'a, 'b> {
@@ -38,9 +94,33 @@ impl<'a, 'b> ParseResult<'a, 'b> {
         ParseResult {
             parser: parser,
             value: value,
+            match_len: value.len(),
+            values: Vec::new(),
         }
     }
 
+    // the length of the consumed text, it's the length of the value by default
+    pub fn match_len(&self) -> usize {
+        self.match_len
+    }
+
+    pub fn set_match_len(&mut self, match_len: usize) {
+        self.match_len = match_len;
+    }
+
+    // parsers can emit key-value pairs besides the parsed value
+    pub fn push_value(&mut self, key: String, value: String) {
+        self.values.push((key, value));
+    }
+
+    pub fn values(&self) -> &[(String, String)] {
+        &self.values
+    }
+
+    pub fn into_values(self) -> Vec<(String, String)> {
+        self.values
+    }
+
     pub fn parser(&self) -> &'a Parser {
         self.parser
     }
@@ -58,5 +138,8 @@ pub trait ParserFactory: {
     fn new_int(name: Option<&str>,
                    opt_params: Option<Vec<OptionalParameter>>)
                    -> Box<Parser>;
-    fn new_greedy(name: Option<&str>, end_string: Option<&str>) -> Box<Parser>;
+    fn new_greedy<'a>(name: Option<&str>,
+                      end_string: Option<&str>,
+                      opt_params: Option<Vec<OptionalParameter<'a>>>)
+                      -> Box<Parser>;
 }
@@ -0,0 +1,430 @@
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use parsers::{Parser, ParserFactory, OptionalParameter, KVParser, JSONParser,
+              CSVParser, TimestampParser, TypedParser, ValueType,
+              GreedyMode, EStringParser, CaseInsensitiveParser};
+use parsers::timestamp::{BSD_FORMAT, ISO_FORMAT, CLF_FORMAT, EPOCH_FORMAT};
+
+pub type ParserConstructor = Fn(ParserParams) -> Result<Box<Parser>, ParamError> + Send + Sync;
+
+static GLOBAL_REGISTRY: RwLock<Option<Arc<ParserRegistry>>> = RwLock::new(None);
+
+#[derive(Debug)]
+pub enum ParserArgument<'a> {
+    Positional(&'a str),
+    Optional(OptionalParameter<'a>),
+}
+
+#[derive(Debug)]
+pub struct ParserParams<'a> {
+    name: Option<&'a str>,
+    positional: Vec<&'a str>,
+    optional: Vec<OptionalParameter<'a>>,
+    end_string: Option<&'a str>,
+}
+
+impl<'a> ParserParams<'a> {
+    pub fn new(name: Option<&'a str>) -> ParserParams<'a> {
+        ParserParams {
+            name: name,
+            positional: Vec::new(),
+            optional: Vec::new(),
+            end_string: None,
+        }
+    }
+
+    pub fn from_arguments(name: Option<&'a str>,
+                          arguments: Vec<ParserArgument<'a>>)
+                          -> ParserParams<'a> {
+        let mut params = ParserParams::new(name);
+        for argument in arguments {
+            match argument {
+                ParserArgument::Positional(value) => params.positional.push(value),
+                ParserArgument::Optional(param) => params.optional.push(param),
+            }
+        }
+        params
+    }
+
+    pub fn name(&self) -> Option<&'a str> {
+        self.name
+    }
+
+    pub fn positional(&self) -> &[&'a str] {
+        &self.positional
+    }
+
+    pub fn optional(&self) -> &[OptionalParameter<'a>] {
+        &self.optional
+    }
+
+    pub fn into_optional(self) -> Vec<OptionalParameter<'a>> {
+        self.optional
+    }
+
+    // the literal which follows the parser in the pattern (only GREEDY gets it)
+    pub fn end_string(&self) -> Option<&'a str> {
+        self.end_string
+    }
+
+    pub fn set_end_string(&mut self, end_string: Option<&'a str>) {
+        self.end_string = end_string;
+    }
+
+    pub fn push_positional(&mut self, value: &'a str) {
+        self.positional.push(value);
+    }
+
+    pub fn push_optional(&mut self, param: OptionalParameter<'a>) {
+        self.optional.push(param);
+    }
+
+    pub fn expect_positional(&self, count: usize) -> Result<(), ParamError> {
+        if self.positional.len() == count {
+            Ok(())
+        } else {
+            Err(ParamError::WrongArgumentCount {
+                expected: count,
+                got: self.positional.len(),
+            })
+        }
+    }
+
+    pub fn expect_known_keys(&self, known_keys: &[&str]) -> Result<(), ParamError> {
+        for param in &self.optional {
+            if !known_keys.contains(&param.key()) {
+                return Err(ParamError::UnknownParameter(param.key().to_owned()));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn int(&self, key: &str) -> Result<Option<usize>, ParamError> {
+        match self.lookup(key) {
+            Some(&OptionalParameter::Int(_, value)) => Ok(Some(value)),
+            Some(&OptionalParameter::Str(_, value)) => Err(ParamError::invalid_value(key, value)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn string(&self, key: &str) -> Result<Option<&'a str>, ParamError> {
+        match self.lookup(key) {
+            Some(&OptionalParameter::Str(_, value)) => Ok(Some(value)),
+            Some(&OptionalParameter::Int(_, value)) => {
+                Err(ParamError::invalid_value(key, &value.to_string()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // removes the `type` parameter, which is handled by the registry for every parser
+    fn take_value_type(&mut self) -> Result<Option<ValueType>, ParamError> {
+        let value_type = match try!(self.string("type")) {
+            Some(name) => {
+                match ValueType::from_name(name) {
+                    Some(value_type) => Some(value_type),
+                    None => return Err(ParamError::invalid_value("type", name)),
+                }
+            }
+            None => None,
+        };
+        self.optional.retain(|param| param.key() != "type");
+        Ok(value_type)
+    }
+
+    fn lookup(&self, key: &str) -> Option<&OptionalParameter<'a>> {
+        self.optional.iter().rev().find(|param| param.key() == key)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ParamError {
+    UnknownParserType(String),
+    UnknownParameter(String),
+    MissingParameter(String),
+    WrongArgumentCount {
+        expected: usize,
+        got: usize,
+    },
+    InvalidValue {
+        key: String,
+        value: String,
+    },
+}
+
+impl ParamError {
+    pub fn invalid_value(key: &str, value: &str) -> ParamError {
+        ParamError::InvalidValue {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            ParamError::UnknownParserType(ref parser_type) => {
+                fmt.write_fmt(format_args!("Unknown parser type: type={}", parser_type))
+            }
+            ParamError::UnknownParameter(ref key) => {
+                fmt.write_fmt(format_args!("Unknown parser parameter: key={}", key))
+            }
+            ParamError::MissingParameter(ref key) => {
+                fmt.write_fmt(format_args!("Missing parser parameter: key={}", key))
+            }
+            ParamError::WrongArgumentCount{expected, got} => {
+                fmt.write_fmt(format_args!("Wrong number of positional parser arguments: \
+                                            expected={} got={}",
+                                           expected,
+                                           got))
+            }
+            ParamError::InvalidValue{ref key, ref value} => {
+                fmt.write_fmt(format_args!("Invalid parser parameter value: key={} value={}",
+                                           key,
+                                           value))
+            }
+        }
+    }
+}
+
+impl error::Error for ParamError {
+    fn description(&self) -> &str {
+        match *self {
+            ParamError::UnknownParserType(..) => "Unknown parser type",
+            ParamError::UnknownParameter(..) => "Unknown parser parameter",
+            ParamError::MissingParameter(..) => "Missing parser parameter",
+            ParamError::WrongArgumentCount{..} => {
+                "Wrong number of positional parser arguments"
+            }
+            ParamError::InvalidValue{..} => "Invalid parser parameter value",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ParserRegistry {
+    constructors: BTreeMap<String, Arc<ParserConstructor>>,
+}
+
+impl ParserRegistry {
+    pub fn new() -> ParserRegistry {
+        ParserRegistry { constructors: BTreeMap::new() }
+    }
+
+    pub fn from_factory<F: ParserFactory + 'static>() -> ParserRegistry {
+        let mut registry = ParserRegistry::new();
+        registry.register("SET", |params: ParserParams| {
+            try!(params.expect_positional(1));
+            try!(params.expect_known_keys(&["min_len", "max_len"]));
+            let set = params.positional()[0];
+            if set.is_empty() {
+                return Err(ParamError::invalid_value("set", set));
+            }
+            let name = params.name();
+            Ok(F::new_set(set, name, Some(params.into_optional())))
+        });
+        registry.register("INT", |params: ParserParams| {
+            try!(params.expect_positional(0));
+            try!(params.expect_known_keys(&["min_len", "max_len"]));
+            let name = params.name();
+            Ok(F::new_int(name, Some(params.into_optional())))
+        });
+        registry.register("GREEDY", |params: ParserParams| {
+            try!(params.expect_positional(0));
+            try!(params.expect_known_keys(&["mode"]));
+            if let Some(mode) = try!(params.string("mode")) {
+                if GreedyMode::from_name(mode).is_none() {
+                    return Err(ParamError::invalid_value("mode", mode));
+                }
+            }
+            let name = params.name();
+            let end_string = params.end_string();
+            Ok(F::new_greedy(name, end_string, Some(params.into_optional())))
+        });
+        registry.register_builtins();
+        registry
+    }
+
+    // parsers which are not created by a ParserFactory
+    fn register_builtins(&mut self) {
+        self.register("KV", KVParser::from_params);
+        self.register("JSON", JSONParser::from_params);
+        self.register("CSV", CSVParser::from_params);
+        self.register("ESTRING", EStringParser::from_params);
+        self.register("ICASE", CaseInsensitiveParser::from_params);
+        self.register("TIMESTAMP",
+                      |params: ParserParams| TimestampParser::from_params(params, None));
+        self.register("BSD_TIMESTAMP", |params: ParserParams| {
+            TimestampParser::from_params(params, Some(BSD_FORMAT))
+        });
+        self.register("ISO_TIMESTAMP", |params: ParserParams| {
+            TimestampParser::from_params(params, Some(ISO_FORMAT))
+        });
+        self.register("CLF_TIMESTAMP", |params: ParserParams| {
+            TimestampParser::from_params(params, Some(CLF_FORMAT))
+        });
+        self.register("EPOCH_TIMESTAMP", |params: ParserParams| {
+            TimestampParser::from_params(params, Some(EPOCH_FORMAT))
+        });
+    }
+
+    pub fn register<S, C>(&mut self, parser_type: S, constructor: C)
+        where S: Into<String>,
+              C: Fn(ParserParams) -> Result<Box<Parser>, ParamError> + Send + Sync + 'static
+    {
+        self.constructors.insert(parser_type.into(), Arc::new(constructor));
+    }
+
+    pub fn contains(&self, parser_type: &str) -> bool {
+        self.constructors.contains_key(parser_type)
+    }
+
+    // the value type of the created parser can be overridden by the `type` parameter
+    pub fn create(&self,
+                  parser_type: &str,
+                  mut params: ParserParams)
+                  -> Result<Box<Parser>, ParamError> {
+        let constructor = match self.constructors.get(parser_type) {
+            Some(constructor) => constructor,
+            None => return Err(ParamError::UnknownParserType(parser_type.to_owned())),
+        };
+        let value_type = try!(params.take_value_type());
+        let parser = try!(constructor(params));
+        match value_type {
+            Some(value_type) if value_type != parser.value_type() => {
+                Ok(Box::new(TypedParser::new(parser, value_type)))
+            }
+            _ => Ok(parser),
+        }
+    }
+}
+
+impl Default for ParserRegistry {
+    fn default() -> Self {
+        use matcher::trie::parser_factory::TrieParserFactory;
+        ParserRegistry::from_factory::<TrieParserFactory>()
+    }
+}
+
+impl fmt::Debug for ParserRegistry {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let types: Vec<&String> = self.constructors.keys().collect();
+        fmt.debug_struct("ParserRegistry").field("types", &types).finish()
+    }
+}
+
+// Registers a parser type in the process-wide registry which is used when patterns
+// are compiled without an explicit registry (e.g. during the deserialization of
+// pattern files). The registry is copied if it's being used, so the patterns which
+// are compiled meanwhile don't see the new type.
+pub fn register_parser<S, C>(parser_type: S, constructor: C)
+    where S: Into<String>,
+          C: Fn(ParserParams) -> Result<Box<Parser>, ParamError> + Send + Sync + 'static
+{
+    let mut registry = GLOBAL_REGISTRY.write().expect("Parser registry lock is poisoned");
+    let registry = registry.get_or_insert_with(|| Arc::new(ParserRegistry::default()));
+    Arc::make_mut(registry).register(parser_type, constructor);
+}
+
+fn global_registry() -> Arc<ParserRegistry> {
+    if let Some(ref registry) = *GLOBAL_REGISTRY.read().expect("Parser registry lock is poisoned") {
+        return registry.clone();
+    }
+    let mut registry = GLOBAL_REGISTRY.write().expect("Parser registry lock is poisoned");
+    registry.get_or_insert_with(|| Arc::new(ParserRegistry::default())).clone()
+}
+
+// The lock isn't held while f runs, so f and the constructors called by it can
+// register parsers.
+pub fn with_global_registry<T, F>(f: F) -> T
+    where F: FnOnce(&ParserRegistry) -> T
+{
+    f(&global_registry())
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{ParserRegistry, ParserParams, ParamError, OptionalParameter, IntParser,
+                  Parser, ValueType, register_parser};
+
+    #[test]
+    fn test_given_default_registry_when_the_builtin_parsers_are_looked_up_then_they_are_found() {
+        let registry = ParserRegistry::default();
+        assert_eq!(registry.contains("SET"), true);
+        assert_eq!(registry.contains("INT"), true);
+        assert_eq!(registry.contains("GREEDY"), true);
+        assert_eq!(registry.contains("KV"), true);
+        assert_eq!(registry.contains("FOO"), false);
+    }
+
+    #[test]
+    fn test_given_registry_when_an_unknown_parser_type_is_created_then_we_get_an_error() {
+        let registry = ParserRegistry::new();
+        let err = registry.create("INT", ParserParams::new(None)).err().unwrap();
+        assert_eq!(err, ParamError::UnknownParserType("INT".to_owned()));
+    }
+
+    #[test]
+    fn test_given_registry_when_a_custom_parser_is_registered_then_it_receives_the_parameters() {
+        let mut registry = ParserRegistry::new();
+        registry.register("NUMBER", |params: ParserParams| {
+            try!(params.expect_known_keys(&["unit"]));
+            assert_eq!(params.string("unit"), Ok(Some("ms")));
+            Ok(Box::new(IntParser::with_name(params.name().unwrap())) as Box<Parser>)
+        });
+        let mut params = ParserParams::new(Some("duration"));
+        params.push_optional(OptionalParameter::Str("unit", "ms"));
+        let parser = registry.create("NUMBER", params).unwrap();
+        assert_eq!(parser.name(), Some("duration"));
+    }
+
+    #[test]
+    fn test_given_set_parser_when_its_parameters_are_invalid_then_we_get_an_error() {
+        let registry = ParserRegistry::default();
+        let err = registry.create("SET", ParserParams::new(None)).err().unwrap();
+        assert_eq!(err,
+                   ParamError::WrongArgumentCount {
+                       expected: 1,
+                       got: 0,
+                   });
+        let mut params = ParserParams::new(None);
+        params.push_optional(OptionalParameter::Int("foo", 1));
+        let err = registry.create("INT", params).err().unwrap();
+        assert_eq!(err, ParamError::UnknownParameter("foo".to_owned()));
+    }
+
+    #[test]
+    fn test_given_registry_when_the_type_parameter_is_given_then_the_value_type_is_overridden() {
+        let registry = ParserRegistry::default();
+        let mut params = ParserParams::new(None);
+        params.push_positional("0123456789.");
+        params.push_optional(OptionalParameter::Str("type", "float"));
+        params.push_optional(OptionalParameter::Int("min_len", 1));
+        let parser = registry.create("SET", params).unwrap();
+        assert_eq!(parser.value_type(), ValueType::Float);
+        assert_eq!(registry.create("INT", ParserParams::new(None)).unwrap().value_type(),
+                   ValueType::Int);
+
+        let mut params = ParserParams::new(None);
+        params.push_optional(OptionalParameter::Str("type", "uuid"));
+        let err = registry.create("INT", params).err().unwrap();
+        assert_eq!(err, ParamError::invalid_value("type", "uuid"));
+    }
+
+    #[test]
+    fn test_given_global_registry_when_a_constructor_registers_a_parser_then_it_succeeds() {
+        register_parser("TEST_REGISTERING", |params: ParserParams| {
+            register_parser("TEST_REGISTERED", |params: ParserParams| {
+                Ok(Box::new(IntParser::with_name(params.name().unwrap())) as Box<Parser>)
+            });
+            Ok(Box::new(IntParser::with_name(params.name().unwrap())) as Box<Parser>)
+        });
+        assert_eq!(::grammar::parser::pattern("%{TEST_REGISTERED:b}").is_err(), true);
+        assert_eq!(::grammar::parser::pattern("%{TEST_REGISTERING:a}").is_ok(), true);
+        assert_eq!(::grammar::parser::pattern("%{TEST_REGISTERED:b}").is_ok(), true);
+    }
+}
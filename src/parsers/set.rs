@@ -2,12 +2,14 @@ use std::collections::BTreeSet;
 use std::iter::FromIterator;
 use std::hash::{SipHasher, Hash, Hasher};
 
-use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, HasLengthConstraint};
+use parsers::{Parser, ObjectSafeHash, ParseResult, ParserBase, HasLengthConstraint, ParserSyntax};
 
 #[derive(Clone, Debug, Hash)]
 pub struct SetParser {
     base: ParserBase,
-    character_set: BTreeSet<u8>,
+    // the characters are stored instead of the bytes, so the multi-byte characters
+    // can be matched and written back
+    character_set: BTreeSet<char>,
     min_length: Option<usize>,
     max_length: Option<usize>,
 }
@@ -39,17 +41,22 @@ impl SetParser {
         self.character_set = SetParser::create_set_from_str(set);
     }
 
-    fn create_set_from_str(set: &str) -> BTreeSet<u8> {
-        let vset: Vec<u8> = set.bytes().collect();
-        BTreeSet::from_iter(vset)
+    fn create_set_from_str(set: &str) -> BTreeSet<char> {
+        BTreeSet::from_iter(set.chars())
     }
 
+    // the characters of the set in order
+    pub fn character_set(&self) -> String {
+        self.character_set.iter().cloned().collect()
+    }
+
+    // the length of the matching prefix in bytes
     fn calculate_match_length(&self, value: &str) -> usize {
         let mut match_len = 0;
 
-        for c in value.bytes() {
+        for c in value.chars() {
             if self.character_set.contains(&c) {
-                match_len += 1;
+                match_len += c.len_utf8();
             } else {
                 break;
             }
@@ -85,6 +92,27 @@ impl Parser for SetParser {
         }
     }
 
+    fn example(&self) -> Option<String> {
+        let len = self.min_length.unwrap_or(1);
+        self.character_set
+            .iter()
+            .cloned()
+            .find(|c| c.is_ascii())
+            .map(|c| (0..len).map(|_| c).collect())
+    }
+
+    fn syntax(&self) -> Option<ParserSyntax> {
+        let mut syntax = ParserSyntax::new("SET", self.name());
+        syntax.positional(self.character_set());
+        if let Some(min_length) = self.min_length {
+            syntax.int("min_len", min_length);
+        }
+        if let Some(max_length) = self.max_length {
+            syntax.int("max_len", max_length);
+        }
+        Some(syntax)
+    }
+
     fn name(&self) -> Option<&str> {
         self.base.name()
     }
@@ -131,6 +159,16 @@ mod test {
         assert_eq!(res.value(), "11230");
     }
 
+    #[test]
+    fn test_given_set_parser_when_its_example_is_generated_then_it_matches_the_example() {
+        let mut p = SetParser::from_str("test", "ba");
+        assert_eq!(p.example(), Some("a".to_owned()));
+        p.set_min_length(Some(3));
+        let example = p.example().unwrap();
+        assert_eq!(p.parse(&example).map(|res| res.value()), Some("aaa"));
+        assert_eq!(SetParser::from_str("test", "").example(), None);
+    }
+
     #[test]
     fn test_given_minimum_match_length_when_a_match_is_shorter_it_doesnt_count_as_a_match() {
         let mut p = SetParser::from_str("test", "0123");
@@ -157,6 +195,16 @@ mod test {
         assert_eq!(res.value(), "11230");
     }
 
+    #[test]
+    fn test_given_set_parser_with_multibyte_characters_when_it_is_parsed_and_written_then_the_characters_are_kept
+        () {
+        let p = SetParser::from_str("name", "éá");
+        assert_eq!(p.parse("áé ").unwrap().value(), "áé");
+        assert_eq!(p.parse("ő").is_none(), true);
+        assert_eq!(p.character_set(), "áé");
+        assert_eq!(p.syntax().unwrap().to_string(), r#"%{SET("áé"):name}"#);
+    }
+
     use parsers::ObjectSafeHash;
 
     #[test]
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyntaxValue {
+    Int(usize),
+    Str(String),
+}
+
+// The description of a parser as it's written in a pattern, like
+// `%{SET("abc",min_len=2):name}`. The optional parameters are written in the order
+// of their keys, so every parser has one canonical form.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParserSyntax {
+    parser_type: String,
+    positional: Vec<String>,
+    optional: BTreeMap<String, SyntaxValue>,
+    name: Option<String>,
+}
+
+impl ParserSyntax {
+    pub fn new<S: Into<String>>(parser_type: S, name: Option<&str>) -> ParserSyntax {
+        ParserSyntax {
+            parser_type: parser_type.into(),
+            positional: Vec::new(),
+            optional: BTreeMap::new(),
+            name: name.map(|name| name.to_owned()),
+        }
+    }
+
+    pub fn positional<S: Into<String>>(&mut self, value: S) -> &mut ParserSyntax {
+        self.positional.push(value.into());
+        self
+    }
+
+    pub fn int(&mut self, key: &str, value: usize) -> &mut ParserSyntax {
+        self.optional.insert(key.to_owned(), SyntaxValue::Int(value));
+        self
+    }
+
+    pub fn string<S: Into<String>>(&mut self, key: &str, value: S) -> &mut ParserSyntax {
+        self.optional.insert(key.to_owned(), SyntaxValue::Str(value.into()));
+        self
+    }
+
+    pub fn parser_type(&self) -> &str {
+        &self.parser_type
+    }
+
+    pub fn positionals(&self) -> &[String] {
+        &self.positional
+    }
+
+    pub fn optionals(&self) -> &BTreeMap<String, SyntaxValue> {
+        &self.optional
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(|name| &name[..])
+    }
+
+    // the strings can't contain escaped quotes, so a string which contains both a
+    // quotation mark and an apostrophe can't be written in a pattern
+    pub fn is_writable(&self) -> bool {
+        let strings = self.optional.values().filter_map(|value| {
+            match *value {
+                SyntaxValue::Str(ref value) => Some(value),
+                SyntaxValue::Int(_) => None,
+            }
+        });
+        self.positional
+            .iter()
+            .chain(strings)
+            .all(|value| !(value.contains('"') && value.contains('\'')))
+    }
+
+    // the arguments without the `%{`, the type and the name, like `"abc",min_len=2`
+    pub fn arguments(&self) -> String {
+        let mut arguments: Vec<String> = self.positional.iter().map(|value| quote(value)).collect();
+        for (key, value) in &self.optional {
+            match *value {
+                SyntaxValue::Int(value) => arguments.push(format!("{}={}", key, value)),
+                SyntaxValue::Str(ref value) => arguments.push(format!("{}={}", key, quote(value))),
+            }
+        }
+        arguments.join(",")
+    }
+}
+
+// apostrophes are used if the string contains a quotation mark, the strings which
+// contain both are written with quotation marks, but they aren't writable
+fn quote(value: &str) -> String {
+    if value.contains('"') {
+        format!("'{}'", value)
+    } else {
+        format!("\"{}\"", value)
+    }
+}
+
+impl fmt::Display for ParserSyntax {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "%{{{}", self.parser_type));
+        if !self.positional.is_empty() || !self.optional.is_empty() {
+            try!(write!(f, "({})", self.arguments()));
+        }
+        if let Some(ref name) = self.name {
+            try!(write!(f, ":{}", name));
+        }
+        write!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::ParserSyntax;
+
+    #[test]
+    fn test_given_parser_syntax_when_it_is_displayed_then_the_optional_parameters_are_ordered_by_their_keys
+        () {
+        let mut syntax = ParserSyntax::new("SET", Some("name"));
+        syntax.string("type", "int").int("max_len", 3).positional("a\"b");
+        assert_eq!(syntax.to_string(), r#"%{SET('a"b',max_len=3,type="int"):name}"#);
+        assert_eq!(ParserSyntax::new("INT", None).to_string(), "%{INT}");
+    }
+
+    #[test]
+    fn test_given_parser_syntax_when_a_string_contains_both_kinds_of_quotes_then_it_is_not_writable() {
+        let mut syntax = ParserSyntax::new("SET", None);
+        syntax.positional("a\"b");
+        assert_eq!(syntax.is_writable(), true);
+        syntax.string("type", "'\"");
+        assert_eq!(syntax.is_writable(), false);
+    }
+}
@@ -0,0 +1,599 @@
+use std::hash::{SipHasher, Hash, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parsers::{ParserBase, Parser, ObjectSafeHash, ParseResult, ParserParams, ParamError,
+              ValueType, ParserSyntax};
+
+pub const BSD_FORMAT: &'static str = "%b %e %H:%M:%S";
+pub const ISO_FORMAT: &'static str = "%Y-%m-%dT%H:%M:%S%.f%z";
+pub const CLF_FORMAT: &'static str = "%d/%b/%Y:%H:%M:%S %z";
+pub const EPOCH_FORMAT: &'static str = "%s%.f";
+
+const MONTHS: [&'static str; 12] = ["January", "February", "March", "April", "May", "June",
+                                    "July", "August", "September", "October", "November",
+                                    "December"];
+const WEEKDAYS: [&'static str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq)]
+enum Item {
+    Literal(char),
+    // %Y
+    Year,
+    // %m
+    Month,
+    // %d
+    Day,
+    // %e: the day of the month, padded with a space
+    SpaceDay,
+    // %H, %M, %S
+    Hour,
+    Minute,
+    Second,
+    // %b, %B
+    MonthAbbreviation,
+    MonthName,
+    // %a, it's parsed but not checked against the date
+    WeekdayAbbreviation,
+    // %f: the digits of the fractional seconds
+    Fraction,
+    // %.f: an optional '.' or ',' and the digits of the fractional seconds
+    OptionalFraction,
+    // %z: 'Z', +hh:mm or +hhmm
+    Offset,
+    // %s, %Q
+    EpochSeconds,
+    EpochMillis,
+}
+
+#[derive(Debug, Default)]
+struct Timestamp {
+    year: Option<i64>,
+    month: Option<u32>,
+    day: Option<u32>,
+    hour: u32,
+    minute: u32,
+    second: u32,
+    nanos: u32,
+    fraction_digits: usize,
+    offset: Option<i32>,
+    epoch_millis: Option<i64>,
+}
+
+#[derive(Clone, Debug, Hash)]
+pub struct TimestampParser {
+    base: ParserBase,
+    format: String,
+    items: Vec<Item>,
+    default_year: Option<i64>,
+    default_offset: Option<i32>,
+    normalized_name: Option<String>,
+}
+
+impl TimestampParser {
+    pub fn with_name(name: String, format: &str) -> Option<TimestampParser> {
+        TimestampParser::new(format).map(|mut parser| {
+            parser.base = ParserBase::with_name(name);
+            parser
+        })
+    }
+
+    // returns None if the format string is invalid
+    pub fn new(format: &str) -> Option<TimestampParser> {
+        TimestampParser::compile(format).map(|items| {
+            TimestampParser {
+                base: ParserBase::new(),
+                format: format.to_owned(),
+                items: items,
+                default_year: None,
+                default_offset: None,
+                normalized_name: None,
+            }
+        })
+    }
+
+    // the format can be given as the only positional argument, otherwise the default
+    // format is used
+    pub fn from_params(params: ParserParams,
+                       default_format: Option<&str>)
+                       -> Result<Box<Parser>, ParamError> {
+        try!(params.expect_known_keys(&["year", "timezone", "normalize"]));
+        let format = match (params.positional().len(), default_format) {
+            (1, _) => params.positional()[0],
+            (0, Some(format)) => format,
+            (got, _) => {
+                return Err(ParamError::WrongArgumentCount {
+                    expected: 1,
+                    got: got,
+                })
+            }
+        };
+        let mut parser = match TimestampParser::new(format) {
+            Some(parser) => parser,
+            None => return Err(ParamError::invalid_value("format", format)),
+        };
+        parser.set_name(params.name().map(|name| name.to_owned()));
+        parser.set_default_year(try!(params.int("year")).map(|year| year as i64));
+        if let Some(timezone) = try!(params.string("timezone")) {
+            match TimestampParser::parse_timezone(timezone) {
+                Some(offset) => parser.set_default_offset(Some(offset)),
+                None => return Err(ParamError::invalid_value("timezone", timezone)),
+            }
+        }
+        parser.set_normalized_name(try!(params.string("normalize")).map(|name| name.to_owned()));
+        Ok(Box::new(parser))
+    }
+
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    // used when the format doesn't contain the year (e.g. BSD timestamps), the
+    // current year is used if it's not set
+    pub fn set_default_year(&mut self, year: Option<i64>) {
+        self.default_year = year;
+    }
+
+    // the offset in seconds which is used when the format doesn't contain a time zone,
+    // UTC is used if it's not set
+    pub fn set_default_offset(&mut self, offset: Option<i32>) {
+        self.default_offset = offset;
+    }
+
+    // if it's set, the timestamp is emitted with this name in ISO 8601 format
+    pub fn set_normalized_name(&mut self, name: Option<String>) {
+        self.normalized_name = name;
+    }
+
+    // accepts 'UTC', 'Z', +hh:mm and +hhmm
+    pub fn parse_timezone(timezone: &str) -> Option<i32> {
+        if timezone == "UTC" {
+            return Some(0);
+        }
+        match TimestampParser::parse_offset(timezone) {
+            Some((offset, len)) if len == timezone.len() => Some(offset),
+            _ => None,
+        }
+    }
+
+    fn compile(format: &str) -> Option<Vec<Item>> {
+        let mut items = Vec::new();
+        let mut chars = format.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                items.push(Item::Literal(c));
+                continue;
+            }
+            let item = match chars.next() {
+                Some('Y') => Item::Year,
+                Some('m') => Item::Month,
+                Some('d') => Item::Day,
+                Some('e') => Item::SpaceDay,
+                Some('H') => Item::Hour,
+                Some('M') => Item::Minute,
+                Some('S') => Item::Second,
+                Some('b') => Item::MonthAbbreviation,
+                Some('B') => Item::MonthName,
+                Some('a') => Item::WeekdayAbbreviation,
+                Some('f') => Item::Fraction,
+                Some('.') if chars.next() == Some('f') => Item::OptionalFraction,
+                Some('z') => Item::Offset,
+                Some('s') => Item::EpochSeconds,
+                Some('Q') => Item::EpochMillis,
+                Some('%') => Item::Literal('%'),
+                _ => return None,
+            };
+            items.push(item);
+        }
+
+        let has_epoch = items.iter()
+                             .any(|item| *item == Item::EpochSeconds || *item == Item::EpochMillis);
+        let has_month = items.iter().any(|item| {
+            *item == Item::Month || *item == Item::MonthAbbreviation || *item == Item::MonthName
+        });
+        let has_day = items.iter().any(|item| *item == Item::Day || *item == Item::SpaceDay);
+
+        if has_epoch || (has_month && has_day) {
+            Some(items)
+        } else {
+            None
+        }
+    }
+
+    // returns the number and the count of the parsed digits
+    fn parse_digits(value: &str, min: usize, max: usize) -> Option<(i64, usize)> {
+        let len = value.bytes().take(max).take_while(|c| (*c as char).is_digit(10)).count();
+        if len < min {
+            None
+        } else {
+            value[..len].parse().ok().map(|number| (number, len))
+        }
+    }
+
+    fn parse_name(value: &str, names: &[&str], len: Option<usize>) -> Option<(usize, usize)> {
+        for (index, name) in names.iter().enumerate() {
+            let name = match len {
+                Some(len) => &name[..len],
+                None => name,
+            };
+            // `get` returns None if the name's length isn't a char boundary of the value
+            if value.get(..name.len()).map_or(false, |prefix| prefix.eq_ignore_ascii_case(name)) {
+                return Some((index, name.len()));
+            }
+        }
+        None
+    }
+
+    // returns the offset in seconds and the length of the parsed text
+    fn parse_offset(value: &str) -> Option<(i32, usize)> {
+        let sign = match value.chars().next() {
+            Some('Z') => return Some((0, 1)),
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => return None,
+        };
+        let (hours, mut len) = match TimestampParser::parse_digits(&value[1..], 2, 2) {
+            Some((hours, _)) if hours < 24 => (hours as i32, 3),
+            _ => return None,
+        };
+        if value[len..].starts_with(':') {
+            len += 1;
+        }
+        match TimestampParser::parse_digits(&value[len..], 2, 2) {
+            Some((minutes, _)) if minutes < 60 => {
+                Some((sign * (hours * 3600 + minutes as i32 * 60), len + 2))
+            }
+            _ => None,
+        }
+    }
+
+    fn parse_fraction(value: &str, timestamp: &mut Timestamp) -> Option<usize> {
+        TimestampParser::parse_digits(value, 1, 9).map(|(fraction, len)| {
+            timestamp.nanos = (fraction * 10i64.pow(9 - len as u32)) as u32;
+            timestamp.fraction_digits = len;
+            len
+        })
+    }
+
+    fn parse_timestamp(&self, value: &str) -> Option<(Timestamp, usize)> {
+        let mut timestamp = Timestamp::default();
+        let mut pos = 0;
+
+        for item in &self.items {
+            let rest = &value[pos..];
+            let len = match *item {
+                Item::Literal(c) => {
+                    if rest.starts_with(c) {
+                        Some(c.len_utf8())
+                    } else {
+                        None
+                    }
+                }
+                Item::Year => {
+                    TimestampParser::parse_digits(rest, 4, 4).map(|(year, len)| {
+                        timestamp.year = Some(year);
+                        len
+                    })
+                }
+                Item::Month => {
+                    TimestampParser::parse_digits(rest, 2, 2).map(|(month, len)| {
+                        timestamp.month = Some(month as u32);
+                        len
+                    })
+                }
+                Item::MonthAbbreviation | Item::MonthName => {
+                    let abbreviation = if *item == Item::MonthAbbreviation {
+                        Some(3)
+                    } else {
+                        None
+                    };
+                    TimestampParser::parse_name(rest, &MONTHS, abbreviation).map(|(index, len)| {
+                        timestamp.month = Some(index as u32 + 1);
+                        len
+                    })
+                }
+                Item::WeekdayAbbreviation => {
+                    TimestampParser::parse_name(rest, &WEEKDAYS, None).map(|(_, len)| len)
+                }
+                Item::Day | Item::SpaceDay => {
+                    let (padding, min) = if *item == Item::SpaceDay && rest.starts_with(' ') {
+                        (1, 1)
+                    } else if *item == Item::SpaceDay {
+                        (0, 1)
+                    } else {
+                        (0, 2)
+                    };
+                    TimestampParser::parse_digits(&rest[padding..], min, 2 - padding)
+                        .map(|(day, len)| {
+                            timestamp.day = Some(day as u32);
+                            padding + len
+                        })
+                }
+                Item::Hour | Item::Minute | Item::Second => {
+                    TimestampParser::parse_digits(rest, 2, 2).map(|(number, len)| {
+                        match *item {
+                            Item::Hour => timestamp.hour = number as u32,
+                            Item::Minute => timestamp.minute = number as u32,
+                            _ => timestamp.second = number as u32,
+                        }
+                        len
+                    })
+                }
+                Item::Fraction => TimestampParser::parse_fraction(rest, &mut timestamp),
+                Item::OptionalFraction => {
+                    if rest.starts_with('.') || rest.starts_with(',') {
+                        TimestampParser::parse_fraction(&rest[1..], &mut timestamp)
+                            .map(|len| len + 1)
+                            .or(Some(0))
+                    } else {
+                        Some(0)
+                    }
+                }
+                Item::Offset => {
+                    TimestampParser::parse_offset(rest).map(|(offset, len)| {
+                        timestamp.offset = Some(offset);
+                        len
+                    })
+                }
+                Item::EpochSeconds | Item::EpochMillis => {
+                    TimestampParser::parse_digits(rest, 1, 15).map(|(epoch, len)| {
+                        timestamp.epoch_millis = Some(if *item == Item::EpochSeconds {
+                            epoch * 1000
+                        } else {
+                            epoch
+                        });
+                        len
+                    })
+                }
+            };
+            match len {
+                Some(len) => pos += len,
+                None => return None,
+            }
+        }
+
+        if self.resolve(&mut timestamp) {
+            Some((timestamp, pos))
+        } else {
+            None
+        }
+    }
+
+    // fills in the missing fields and checks the ranges of the parsed ones
+    fn resolve(&self, timestamp: &mut Timestamp) -> bool {
+        if timestamp.offset.is_none() {
+            timestamp.offset = Some(self.default_offset.unwrap_or(0));
+        }
+
+        if let Some(millis) = timestamp.epoch_millis {
+            let offset = timestamp.offset.unwrap_or(0) as i64;
+            let seconds = millis.div_euclid(1000) + offset;
+            let (year, month, day) = civil_from_days(seconds.div_euclid(86400));
+            let seconds_of_day = seconds.rem_euclid(86400);
+            timestamp.year = Some(year);
+            timestamp.month = Some(month);
+            timestamp.day = Some(day);
+            timestamp.hour = (seconds_of_day / 3600) as u32;
+            timestamp.minute = (seconds_of_day % 3600 / 60) as u32;
+            timestamp.second = (seconds_of_day % 60) as u32;
+            if timestamp.fraction_digits == 0 && millis % 1000 != 0 {
+                timestamp.nanos = (millis.rem_euclid(1000) * 1_000_000) as u32;
+                timestamp.fraction_digits = 3;
+            }
+        }
+
+        if timestamp.year.is_none() {
+            timestamp.year = Some(self.default_year.unwrap_or_else(current_year));
+        }
+
+        let year = timestamp.year.unwrap_or(0);
+        let month = timestamp.month.unwrap_or(0);
+        let day = timestamp.day.unwrap_or(0);
+        month >= 1 && month <= 12 && day >= 1 && day <= days_in_month(year, month) &&
+        timestamp.hour < 24 && timestamp.minute < 60 && timestamp.second <= 60
+    }
+}
+
+impl Timestamp {
+    fn to_iso8601(&self) -> String {
+        let fraction = if self.fraction_digits > 0 {
+            let nanos = self.nanos / 10u32.pow(9 - self.fraction_digits as u32);
+            format!(".{:0width$}", nanos, width = self.fraction_digits)
+        } else {
+            String::new()
+        };
+        let offset = self.offset.unwrap_or(0);
+        let sign = if offset < 0 {
+            '-'
+        } else {
+            '+'
+        };
+        format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{}{:02}:{:02}",
+                self.year.unwrap_or(0),
+                self.month.unwrap_or(0),
+                self.day.unwrap_or(0),
+                self.hour,
+                self.minute,
+                self.second,
+                fraction,
+                sign,
+                offset.abs() / 3600,
+                offset.abs() % 3600 / 60)
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// converts the days since 1970-01-01 to a (year, month, day) triple
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 -
+                       day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 {
+        1
+    } else {
+        0
+    };
+    (year, month, day)
+}
+
+fn current_year() -> i64 {
+    let seconds = SystemTime::now()
+                      .duration_since(UNIX_EPOCH)
+                      .map(|duration| duration.as_secs() as i64)
+                      .unwrap_or(0);
+    civil_from_days(seconds / 86400).0
+}
+
+impl ObjectSafeHash for TimestampParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:timestamp".hash(&mut hasher);
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl Parser for TimestampParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        self.parse_timestamp(value).map(|(timestamp, len)| {
+            let mut result = ParseResult::new(self, &value[..len]);
+            if let Some(ref name) = self.normalized_name {
+                result.push_value(name.clone(), timestamp.to_iso8601());
+            }
+            result
+        })
+    }
+
+    fn syntax(&self) -> Option<ParserSyntax> {
+        let mut syntax = ParserSyntax::new("TIMESTAMP", self.name());
+        syntax.positional(&self.format[..]);
+        if let Some(year) = self.default_year {
+            syntax.int("year", year as usize);
+        }
+        if let Some(offset) = self.default_offset {
+            let sign = if offset < 0 { '-' } else { '+' };
+            let offset = offset.abs();
+            syntax.string("timezone",
+                          format!("{}{:02}:{:02}", sign, offset / 3600, offset % 3600 / 60));
+        }
+        if let Some(ref name) = self.normalized_name {
+            syntax.string("normalize", &name[..]);
+        }
+        Some(syntax)
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.base.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.base.set_name(name);
+    }
+
+    fn value_type(&self) -> ValueType {
+        ValueType::Timestamp
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{TimestampParser, Parser};
+    use parsers::timestamp::{BSD_FORMAT, ISO_FORMAT, CLF_FORMAT, EPOCH_FORMAT};
+
+    fn normalized(format: &str, value: &str) -> Option<(String, String)> {
+        let mut parser = TimestampParser::new(format).unwrap();
+        parser.set_default_year(Some(2016));
+        parser.set_normalized_name(Some("ts".to_owned()));
+        parser.parse(value).map(|res| (res.value().to_owned(), res.into_values().remove(0).1))
+    }
+
+    fn pair(raw: &str, iso: &str) -> Option<(String, String)> {
+        Some((raw.to_owned(), iso.to_owned()))
+    }
+
+    #[test]
+    fn test_given_bsd_timestamp_parser_when_the_month_is_not_ascii_then_it_doesnt_match() {
+        let parser = TimestampParser::new(BSD_FORMAT).unwrap();
+        assert_eq!(parser.parse("ééé 1 14:09:58").is_none(), true);
+        assert_eq!(parser.parse("Jé 1 14:09:58").is_none(), true);
+        assert_eq!(parser.parse("ééé 1").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_timestamp_parser_when_the_format_is_invalid_then_it_cannot_be_created() {
+        assert_eq!(TimestampParser::new("%Y-%m-%q").is_none(), true);
+        assert_eq!(TimestampParser::new("%H:%M:%S").is_none(), true);
+        assert_eq!(TimestampParser::new("%Y-%m-%d").is_some(), true);
+    }
+
+    #[test]
+    fn test_given_bsd_timestamp_parser_when_the_timestamp_is_parsed_then_the_default_year_and_zone_are_used
+        () {
+        assert_eq!(normalized(BSD_FORMAT, "Jun 25 14:09:58 host"),
+                   pair("Jun 25 14:09:58", "2016-06-25T14:09:58+00:00"));
+        assert_eq!(normalized(BSD_FORMAT, "Jun  5 14:09:58"),
+                   pair("Jun  5 14:09:58", "2016-06-05T14:09:58+00:00"));
+
+        let mut parser = TimestampParser::new(BSD_FORMAT).unwrap();
+        parser.set_default_year(Some(2015));
+        parser.set_default_offset(TimestampParser::parse_timezone("-05:30"));
+        parser.set_normalized_name(Some("ts".to_owned()));
+        assert_eq!(parser.parse("Feb 28 01:02:03").unwrap().into_values(),
+                   vec![("ts".to_owned(), "2015-02-28T01:02:03-05:30".to_owned())]);
+        assert_eq!(parser.parse("Feb 29 01:02:03").is_none(), true);
+    }
+
+    #[test]
+    fn test_given_iso_and_clf_timestamp_parsers_when_the_timestamp_is_parsed_then_its_offset_is_kept
+        () {
+        assert_eq!(normalized(ISO_FORMAT, "2016-06-25T14:09:58.12+02:00 x"),
+                   pair("2016-06-25T14:09:58.12+02:00", "2016-06-25T14:09:58.12+02:00"));
+        assert_eq!(normalized(ISO_FORMAT, "2016-06-25T14:09:58Z"),
+                   pair("2016-06-25T14:09:58Z", "2016-06-25T14:09:58+00:00"));
+        assert_eq!(normalized(ISO_FORMAT, "2016-13-25T14:09:58Z"), None);
+        assert_eq!(normalized(CLF_FORMAT, "10/Oct/2000:13:55:36 -0700]"),
+                   pair("10/Oct/2000:13:55:36 -0700", "2000-10-10T13:55:36-07:00"));
+    }
+
+    #[test]
+    fn test_given_epoch_timestamp_parser_when_the_timestamp_is_parsed_then_it_is_converted_to_a_date
+        () {
+        assert_eq!(normalized(EPOCH_FORMAT, "1466863798 x"),
+                   pair("1466863798", "2016-06-25T14:09:58+00:00"));
+        assert_eq!(normalized(EPOCH_FORMAT, "1466863798.5"),
+                   pair("1466863798.5", "2016-06-25T14:09:58.5+00:00"));
+        assert_eq!(normalized("%Q", "1466863798123"),
+                   pair("1466863798123", "2016-06-25T14:09:58.123+00:00"));
+        assert_eq!(normalized("%s", "-86400"), None);
+        assert_eq!(normalized(EPOCH_FORMAT, "1466863798. x"),
+                   pair("1466863798", "2016-06-25T14:09:58+00:00"));
+        assert_eq!(normalized("%s", "0"), pair("0", "1970-01-01T00:00:00+00:00"));
+    }
+}
@@ -0,0 +1,125 @@
+use std::hash::{SipHasher, Hash, Hasher};
+
+use parsers::{Parser, ObjectSafeHash, ParseResult, ValueType, ParserSyntax};
+
+// Overrides the value type of an other parser. It matches only if the parsed value
+// can be converted to the given type.
+#[derive(Debug)]
+pub struct TypedParser {
+    delegate: Box<Parser>,
+    value_type: ValueType,
+}
+
+impl TypedParser {
+    pub fn new(delegate: Box<Parser>, value_type: ValueType) -> TypedParser {
+        TypedParser {
+            delegate: delegate,
+            value_type: value_type,
+        }
+    }
+
+    // skips the matches of the delegate which cannot be converted to the value type
+    fn retype<'a, 'b>(&'a self,
+                      value: &'b str,
+                      mut next_result: Option<ParseResult<'a, 'b>>)
+                      -> Option<ParseResult<'a, 'b>> {
+        while let Some(result) = next_result {
+            let parsed_len = result.match_len();
+            if self.value_type.convert(result.value()).is_some() {
+                let mut typed_result = ParseResult::new(self, result.value());
+                typed_result.set_match_len(parsed_len);
+                for (key, value) in result.into_values() {
+                    typed_result.push_value(key, value);
+                }
+                return Some(typed_result);
+            }
+            next_result = self.delegate.parse_next(value, parsed_len);
+        }
+        None
+    }
+}
+
+impl Clone for TypedParser {
+    fn clone(&self) -> TypedParser {
+        TypedParser::new(self.delegate.boxed_clone(), self.value_type)
+    }
+}
+
+impl Parser for TypedParser {
+    fn parse<'a, 'b>(&'a self, value: &'b str) -> Option<ParseResult<'a, 'b>> {
+        self.retype(value, self.delegate.parse(value))
+    }
+
+    fn parse_next<'a, 'b>(&'a self,
+                          value: &'b str,
+                          previous_len: usize)
+                          -> Option<ParseResult<'a, 'b>> {
+        self.retype(value, self.delegate.parse_next(value, previous_len))
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.delegate.name()
+    }
+
+    fn set_name(&mut self, name: Option<String>) {
+        self.delegate.set_name(name);
+    }
+
+    fn set_whitespace_tolerant(&mut self, whitespace_tolerant: bool) {
+        self.delegate.set_whitespace_tolerant(whitespace_tolerant);
+    }
+
+    fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    fn example(&self) -> Option<String> {
+        self.delegate.example().and_then(|example| {
+            self.parse(&example).and_then(|result| {
+                if result.match_len() == example.len() {
+                    Some(example.clone())
+                } else {
+                    None
+                }
+            })
+        })
+    }
+
+    fn syntax(&self) -> Option<ParserSyntax> {
+        self.delegate.syntax().map(|mut syntax| {
+            syntax.string("type", self.value_type.name());
+            syntax
+        })
+    }
+
+    fn boxed_clone(&self) -> Box<Parser> {
+        Box::new(self.clone())
+    }
+}
+
+impl ObjectSafeHash for TypedParser {
+    fn hash_os(&self) -> u64 {
+        let mut hasher = SipHasher::new();
+        "parser:typed".hash(&mut hasher);
+        self.delegate.hash_os().hash(&mut hasher);
+        self.value_type.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{TypedParser, SetParser, Parser, ValueType};
+
+    #[test]
+    fn test_given_typed_parser_when_the_value_cannot_be_converted_then_it_doesnt_match() {
+        let delegate = SetParser::with_name("ratio".to_owned(), "0123456789.");
+        let parser = TypedParser::new(Box::new(delegate), ValueType::Float);
+        assert_eq!(parser.value_type(), ValueType::Float);
+        assert_eq!(parser.parse("1.2.3").is_none(), true);
+        let res = parser.parse("0.25 x").unwrap();
+        assert_eq!(res.value(), "0.25");
+        assert_eq!(res.parser().value_type(), ValueType::Float);
+        assert_eq!(res.parser().name(), Some("ratio"));
+    }
+}
@@ -0,0 +1,240 @@
+use std::error;
+use std::fmt;
+use std::net::IpAddr;
+
+use serde;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum ValueType {
+    String,
+    Int,
+    Float,
+    Bool,
+    IpAddr,
+    Timestamp,
+}
+
+impl ValueType {
+    pub fn from_name(name: &str) -> Option<ValueType> {
+        match name {
+            "string" => Some(ValueType::String),
+            "int" => Some(ValueType::Int),
+            "float" => Some(ValueType::Float),
+            "bool" => Some(ValueType::Bool),
+            "ip" => Some(ValueType::IpAddr),
+            "timestamp" => Some(ValueType::Timestamp),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            ValueType::String => "string",
+            ValueType::Int => "int",
+            ValueType::Float => "float",
+            ValueType::Bool => "bool",
+            ValueType::IpAddr => "ip",
+            ValueType::Timestamp => "timestamp",
+        }
+    }
+
+    // returns None if the value cannot be represented by this type
+    pub fn convert(&self, value: &str) -> Option<TypedValue> {
+        match *self {
+            ValueType::String => Some(TypedValue::String(value.to_owned())),
+            ValueType::Int => value.parse().ok().map(TypedValue::Int),
+            ValueType::Float => {
+                value.parse::<f64>().ok().and_then(|value| {
+                    if value.is_finite() {
+                        Some(TypedValue::Float(value))
+                    } else {
+                        None
+                    }
+                })
+            }
+            ValueType::Bool => {
+                match value {
+                    "true" | "1" => Some(TypedValue::Bool(true)),
+                    "false" | "0" => Some(TypedValue::Bool(false)),
+                    _ => None,
+                }
+            }
+            ValueType::IpAddr => value.parse().ok().map(TypedValue::IpAddr),
+            ValueType::Timestamp => Some(TypedValue::Timestamp(value.to_owned())),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypedValue {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    IpAddr(IpAddr),
+    // the text of the timestamp as it was parsed
+    Timestamp(String),
+}
+
+impl TypedValue {
+    pub fn value_type(&self) -> ValueType {
+        match *self {
+            TypedValue::String(..) => ValueType::String,
+            TypedValue::Int(..) => ValueType::Int,
+            TypedValue::Float(..) => ValueType::Float,
+            TypedValue::Bool(..) => ValueType::Bool,
+            TypedValue::IpAddr(..) => ValueType::IpAddr,
+            TypedValue::Timestamp(..) => ValueType::Timestamp,
+        }
+    }
+}
+
+impl fmt::Display for TypedValue {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            TypedValue::String(ref value) | TypedValue::Timestamp(ref value) => {
+                fmt.write_str(value)
+            }
+            TypedValue::Int(value) => value.fmt(fmt),
+            TypedValue::Float(value) => value.fmt(fmt),
+            TypedValue::Bool(value) => value.fmt(fmt),
+            TypedValue::IpAddr(ref value) => value.fmt(fmt),
+        }
+    }
+}
+
+// the value cannot be represented by the type
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConversionError {
+    value_type: ValueType,
+    value: String,
+}
+
+impl ConversionError {
+    pub fn new(value_type: ValueType, value: &str) -> ConversionError {
+        ConversionError {
+            value_type: value_type,
+            value: value.to_owned(),
+        }
+    }
+
+    pub fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        fmt.write_fmt(format_args!("Value cannot be converted: type={} value={}",
+                                   self.value_type.name(),
+                                   self.value))
+    }
+}
+
+impl error::Error for ConversionError {
+    fn description(&self) -> &str {
+        "Value cannot be converted"
+    }
+}
+
+// IP addresses and timestamps are serialized as strings
+impl serde::Serialize for TypedValue {
+    fn serialize<S>(&self, serializer: &mut S) -> Result<(), S::Error>
+        where S: serde::Serializer
+    {
+        match *self {
+            TypedValue::String(ref value) | TypedValue::Timestamp(ref value) => {
+                serializer.serialize_str(value)
+            }
+            TypedValue::Int(value) => serializer.serialize_i64(value),
+            TypedValue::Float(value) => serializer.serialize_f64(value),
+            TypedValue::Bool(value) => serializer.serialize_bool(value),
+            TypedValue::IpAddr(ref value) => serializer.serialize_str(&value.to_string()),
+        }
+    }
+}
+
+// strings are always deserialized as TypedValue::String
+impl serde::Deserialize for TypedValue {
+    fn deserialize<D>(deserializer: &mut D) -> Result<TypedValue, D::Error>
+        where D: serde::de::Deserializer
+    {
+        struct TypedValueVisitor;
+
+        impl serde::de::Visitor for TypedValueVisitor {
+            type Value = TypedValue;
+
+            fn visit_bool<E>(&mut self, value: bool) -> Result<TypedValue, E>
+                where E: serde::de::Error
+            {
+                Ok(TypedValue::Bool(value))
+            }
+
+            fn visit_i64<E>(&mut self, value: i64) -> Result<TypedValue, E>
+                where E: serde::de::Error
+            {
+                Ok(TypedValue::Int(value))
+            }
+
+            fn visit_u64<E>(&mut self, value: u64) -> Result<TypedValue, E>
+                where E: serde::de::Error
+            {
+                if value <= i64::max_value() as u64 {
+                    Ok(TypedValue::Int(value as i64))
+                } else {
+                    Err(serde::de::Error::custom(format!("Integer is out of range: {}", value)))
+                }
+            }
+
+            fn visit_f64<E>(&mut self, value: f64) -> Result<TypedValue, E>
+                where E: serde::de::Error
+            {
+                Ok(TypedValue::Float(value))
+            }
+
+            fn visit_str<E>(&mut self, value: &str) -> Result<TypedValue, E>
+                where E: serde::de::Error
+            {
+                Ok(TypedValue::String(value.to_owned()))
+            }
+        }
+
+        deserializer.deserialize(TypedValueVisitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use parsers::{ValueType, TypedValue};
+    use serde_json;
+
+    #[test]
+    fn test_given_value_type_when_a_value_is_converted_then_invalid_values_are_rejected() {
+        assert_eq!(ValueType::Int.convert("-42"), Some(TypedValue::Int(-42)));
+        assert_eq!(ValueType::Int.convert("4a"), None);
+        assert_eq!(ValueType::Float.convert("1.5"), Some(TypedValue::Float(1.5)));
+        assert_eq!(ValueType::Float.convert("inf"), None);
+        assert_eq!(ValueType::Bool.convert("false"), Some(TypedValue::Bool(false)));
+        assert_eq!(ValueType::IpAddr.convert("::1").map(|ip| ip.to_string()),
+                   Some("::1".to_owned()));
+        assert_eq!(ValueType::IpAddr.convert("1.2.3"), None);
+    }
+
+    #[test]
+    fn test_given_typed_values_when_they_are_serialized_and_deserialized_then_their_types_are_kept
+        () {
+        let values = vec![TypedValue::Int(1),
+                          TypedValue::Float(0.5),
+                          TypedValue::Bool(true),
+                          TypedValue::String("x".to_owned())];
+        let json = serde_json::to_string(&values).unwrap();
+        assert_eq!(json, r#"[1,0.5,true,"x"]"#);
+        assert_eq!(serde_json::from_str::<Vec<TypedValue>>(&json).unwrap(), values);
+        let ip = ValueType::IpAddr.convert("1.2.3.4").unwrap();
+        assert_eq!(serde_json::to_string(&ip).unwrap(), r#""1.2.3.4""#);
+    }
+}
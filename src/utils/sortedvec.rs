@@ -43,6 +43,10 @@ impl <T: Ord> SortedVec<T> {
         self.array.len()
     }
 
+    pub fn iter(&self) -> ::std::slice::Iter<T> {
+        self.array.iter()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.array.is_empty()
     }
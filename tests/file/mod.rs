@@ -1,7 +1,11 @@
 use actiondb::matcher::PatternLoader;
 use actiondb::matcher::BuildError;
+use actiondb::matcher::pattern::file::FileFormat;
+use actiondb::matcher::trie::factory::TrieMatcherFactory;
 
 use std::env;
+use std::fs::File;
+use std::io::{Cursor, Read};
 
 const DIR_PREFIX: &'static str = "tests/file";
 
@@ -55,3 +59,51 @@ fn test_given_not_existing_yaml_pattern_file_when_it_is_opened_we_get_io_error()
                    .err()
                    .expect("Successfully opened a non-existing YAML file");
 }
+
+#[test]
+fn test_given_pattern_file_without_extension_when_it_is_loaded_then_its_format_is_detected() {
+    let file = PatternLoader::load_file("tests/file/ssh_ok_yaml")
+                   .ok()
+                   .expect("Failed to detect the format of a YAML pattern file");
+    assert_eq!(file.patterns().len(), 3);
+}
+
+#[test]
+fn test_given_file_with_unknown_extension_when_it_is_not_a_pattern_file_then_we_get_unknown_format_error
+    () {
+    match PatternLoader::load_file("tests/ssh_log.txt") {
+        Err(BuildError::UnknownFileFormat) => {}
+        Ok(_) | Err(_) => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_pattern_file_content_when_it_is_loaded_with_its_format_then_we_get_the_patterns() {
+    let mut content = String::new();
+    let _ = File::open("tests/file/ssh_ok.yaml").unwrap().read_to_string(&mut content);
+    let file = PatternLoader::load_str(&content, FileFormat::Yaml)
+                   .ok()
+                   .expect("Failed to load YAML serialized pattern file from a string");
+    assert_eq!(file.patterns().len(), 3);
+
+    match PatternLoader::load_str(&content, FileFormat::Json) {
+        Err(BuildError::DeserJson(_)) => {}
+        Ok(_) | Err(_) => unreachable!(),
+    }
+}
+
+#[test]
+fn test_given_reader_when_the_patterns_are_loaded_from_it_then_we_get_a_matcher() {
+    let mut reader = File::open("tests/file/ssh_ok.json").unwrap();
+    let file = PatternLoader::load_reader(&mut reader, FileFormat::Json)
+                   .ok()
+                   .expect("Failed to load JSON serialized pattern file from a reader");
+    assert_eq!(file.patterns().len(), 3);
+
+    let mut reader = Cursor::new("patterns:\n  - uuid: 9a49c47d-29e9-4072-be84-3b76c6814743\n    \
+                                  pattern: \"user %{INT:uid} logged in\"\n");
+    let matcher = PatternLoader::from_reader::<TrieMatcherFactory, _>(&mut reader, FileFormat::Yaml)
+                      .ok()
+                      .expect("Failed to build a matcher from a reader");
+    assert!(matcher.parse("user 1 logged in").is_some());
+}
@@ -1,7 +1,10 @@
 extern crate actiondb;
 
-use actiondb::matcher::PatternLoader;
+use actiondb::matcher::{Matcher, PatternLoader};
+use actiondb::matcher::counter::CountingMatcherFactory;
 use actiondb::matcher::trie::factory::TrieMatcherFactory;
+use actiondb::matcher::BuildError;
+use actiondb::matcher::pattern::testmessage;
 
 #[test]
 fn test_given_json_file_when_its_syntax_is_ok_then_matcher_can_be_built_from_it() {
@@ -90,3 +93,91 @@ fn test_given_json_file_when_we_check_the_test_messages_then_the_resulting_patte
                    .expect("The UUID of the resulting pattern should be the same as the freshly \
                             inserted one");
 }
+
+#[test]
+fn test_given_json_file_when_the_test_messages_contain_typed_values_then_they_are_compared_with_the_typed_parsed_values
+    () {
+    let pattern_file_path = "tests/matcher/ssh_typed_values.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.ok().expect("The typed test values should equal to the typed parsed values");
+}
+
+#[test]
+fn test_given_json_file_when_a_typed_test_value_is_compared_with_a_string_value_then_we_fail() {
+    let pattern_file_path = "tests/matcher/ssh_typed_value_does_not_match.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    let _ = matcher.err().expect("An integer test value should not equal to a string value");
+}
+
+#[test]
+fn test_given_json_file_when_it_is_whitespace_tolerant_then_its_patterns_can_override_it() {
+    let pattern_file_path = "tests/matcher/ssh_whitespace_tolerant.json";
+    let matcher = PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path);
+    println!("matcher: {:?}", &matcher);
+    match matcher {
+        Err(BuildError::TestMessage(testmessage::Error::TestMessageDoesntMatch{pattern_uuid, ..})) => {
+            assert_eq!(pattern_uuid, "c3a5c8f7-3a9e-4f0e-8a57-1a63b2f8d2e4");
+        }
+        result => {
+            panic!("A pattern which is not whitespace tolerant matched a message with different \
+                    whitespace: {:?}",
+                   result)
+        }
+    }
+}
+
+#[test]
+fn test_given_json_file_when_a_message_which_must_not_match_any_pattern_matches_a_later_pattern_then_we_fail
+    () {
+    let pattern_file_path = "tests/matcher/ssh_negative_test_messages.json";
+    match PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path) {
+        Err(BuildError::TestMessage(testmessage::Error::UnexpectedMatch{got_uuid, ..})) => {
+            assert_eq!(got_uuid, "8e1f6a3b-0c2d-4e5f-9a6b-7c8d9e0f1a2b")
+        }
+        result => panic!("Unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn test_given_json_file_when_a_message_which_must_not_match_its_pattern_matches_it_then_we_fail() {
+    let pattern_file_path = "tests/matcher/ssh_negative_test_message_matches.json";
+    match PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path) {
+        Err(BuildError::TestMessage(testmessage::Error::UnexpectedMatch{pattern_uuid, got_uuid, ..})) => {
+            assert_eq!(pattern_uuid, got_uuid)
+        }
+        result => panic!("Unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn test_given_json_file_with_strict_test_messages_when_a_parser_extracts_an_unexpected_key_then_we_fail
+    () {
+    let pattern_file_path = "tests/matcher/ssh_strict_test_messages.json";
+    match PatternLoader::from_file::<TrieMatcherFactory>(pattern_file_path) {
+        Err(BuildError::TestMessage(testmessage::Error::UnexpectedKey{key, ..})) => {
+            assert_eq!(key, "usr")
+        }
+        result => panic!("Unexpected result: {:?}", result),
+    }
+}
+
+#[test]
+fn test_given_json_file_when_a_counting_matcher_is_built_from_it_then_the_hits_of_its_patterns_are_counted
+    () {
+    let pattern_file_path = "tests/matcher/ssh_ok.json";
+    let matcher = PatternLoader::from_file::<CountingMatcherFactory<TrieMatcherFactory>>(pattern_file_path)
+                      .expect("Failed to create a CountingMatcher from a valid JSON pattern file");
+    matcher.reset();
+    let message = "Jun 25 14:09:41 lobotomy sshd[26478]: pam_unix(sshd:session): session closed for user zts";
+    assert!(matcher.parse(message).is_some());
+    assert!(matcher.parse("Jun 25 14:09:41 lobotomy sshd[26478]: unknown").is_none());
+
+    let counts = matcher.snapshot();
+    assert_eq!(counts.hits().len(), 3);
+    assert_eq!(counts.hits_of(&"fa8bdbcb-e0fd-4da1-9fa4-15ecfec28ad2".parse().unwrap()), Some(1));
+    assert_eq!(counts.unmatched(), 1);
+    assert_eq!(counts.never_matched().len(), 2);
+    assert_eq!(counts.untracked(), 0);
+}